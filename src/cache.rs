@@ -0,0 +1,258 @@
+//! Capture and replay of asset values.
+//!
+//! Values in a `ValuesCache` are type erased, so they can not be serialized generically.
+//! Each type to be stored must be registered in a `CacheCodec` together with a tag, which
+//! identifies the type in the stored data, and the functions to encode and decode it.
+
+use super::*;
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+type Encoder = Box<dyn Fn(&dyn Any) -> Option<Vec<u8>>>;
+type Decoder = Box<dyn Fn(&[u8]) -> Option<Rc<dyn Any>>>;
+
+/// Registry of the functions used to (de)serialize each type of asset
+#[derive(Default)]
+pub struct CacheCodec {
+    encoders: Map<TypeId, (String, Encoder)>,
+    decoders: Map<String, Decoder>,
+}
+
+impl CacheCodec {
+    pub fn new() -> CacheCodec {
+        CacheCodec {
+            ..Default::default()
+        }
+    }
+
+    /// registers a type, `tag` must be unique and stable between runs
+    pub fn register<T, E, D>(&mut self, tag: &str, encode: E, decode: D)
+    where
+        T: 'static,
+        E: 'static + Fn(&T) -> Vec<u8>,
+        D: 'static + Fn(&[u8]) -> Option<T>,
+    {
+        let encoder: Encoder = Box::new(move |value: &dyn Any| value.downcast_ref::<T>().map(&encode));
+        let decoder: Decoder = Box::new(move |bytes: &[u8]| {
+            decode(bytes).map(|value| Rc::new(value) as Rc<dyn Any>)
+        });
+        self.encoders.insert(TypeId::of::<T>(), (tag.into(), encoder));
+        self.decoders.insert(tag.into(), decoder);
+    }
+
+    fn encode(&self, value: &dyn Any) -> Option<(&str, Vec<u8>)> {
+        let (tag, encoder) = self.encoders.get(&value.type_id())?;
+        encoder(value).map(|bytes| (tag.as_str(), bytes))
+    }
+
+    fn decode(&self, tag: &str, bytes: &[u8]) -> Option<Rc<dyn Any>> {
+        self.decoders.get(tag).and_then(|decoder| decoder(bytes))
+    }
+}
+
+/// Writes the cache into a file. Only values of types registered in the codec are
+/// stored, the rest are skipped. Returns the number of values written.
+pub fn dump<P: AsRef<Path>>(cache: &ValuesCache, path: P, codec: &CacheCodec) -> io::Result<usize> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let count = stream_dump(cache, &mut out, codec)?;
+    out.flush()?;
+    Ok(count)
+}
+
+/// Writes the cache into any output, as `dump` does. Values are encoded and written one
+/// at a time, so only one encoded value is held in memory.
+pub fn stream_dump<W: Write>(cache: &ValuesCache, out: &mut W, codec: &CacheCodec) -> io::Result<usize> {
+    let mut count = 0;
+    for (name, value) in cache.iter() {
+        if let Some((tag, bytes)) = codec.encode(value.as_ref()) {
+            write_chunk(out, tag.as_bytes())?;
+            write_chunk(out, name.as_bytes())?;
+            write_chunk(out, &bytes)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Reads a cache previously written with `dump`, all types stored must be registered in
+/// the codec.
+pub fn load<P: AsRef<Path>>(path: P, codec: &CacheCodec) -> io::Result<ValuesCache> {
+    stream_load(&mut BufReader::new(File::open(path)?), codec)
+}
+
+/// Reads a cache from any input, as written by `stream_dump`.
+pub fn stream_load<R: Read>(input: &mut R, codec: &CacheCodec) -> io::Result<ValuesCache> {
+    let mut cache = ValuesCache::new();
+
+    while let Some(tag) = read_chunk(input)? {
+        let tag = into_string(tag)?;
+        let name = read_chunk(input)?.ok_or_else(truncated)?;
+        let name = into_string(name)?;
+        let bytes = read_chunk(input)?.ok_or_else(truncated)?;
+
+        let value = codec.decode(&tag, &bytes).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("can not decode {} as {}", name, tag),
+            )
+        })?;
+        cache.insert(name, value);
+    }
+    Ok(cache)
+}
+
+fn write_chunk<W: Write>(out: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len()).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "cache record larger than 4GiB")
+    })?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(bytes)
+}
+
+/// reads a length prefixed chunk, None at the end of the input. The end must fall between
+/// chunks, a partial length or payload is an error.
+fn read_chunk<R: Read>(input: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    let mut filled = 0;
+    while filled < len.len() {
+        match input.read(&mut len[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(truncated()),
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    // the length is not trusted, the buffer grows as the payload is read
+    let len = u64::from(u32::from_le_bytes(len));
+    let mut bytes = Vec::new();
+    input.by_ref().take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(truncated());
+    }
+    Ok(Some(bytes))
+}
+
+fn into_string(bytes: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "truncated cache record")
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::convert::TryInto;
+
+    fn get_test_codec() -> CacheCodec {
+        let mut codec = CacheCodec::new();
+        codec.register::<u32, _, _>(
+            "u32",
+            |v| v.to_le_bytes().to_vec(),
+            |b| Some(u32::from_le_bytes(b.try_into().ok()?)),
+        );
+        codec.register::<f32, _, _>(
+            "f32",
+            |v| v.to_le_bytes().to_vec(),
+            |b| Some(f32::from_le_bytes(b.try_into().ok()?)),
+        );
+        codec
+    }
+
+    #[test]
+    fn round_trip() {
+        let codec = get_test_codec();
+        let mut cache = ValuesCache::new();
+        cache.save_value_str("task::int", 42u32);
+        cache.save_value_str("task::float", 1.5f32);
+        cache.save_value_str("task::unregistered", String::from("skipped"));
+
+        let path = std::env::temp_dir().join("rgraph_cache_round_trip.bin");
+        assert!(dump(&cache, &path, &codec).expect("could not write") == 2);
+
+        let loaded = load(&path, &codec).expect("could not read");
+        std::fs::remove_file(&path).ok();
+
+        assert!(loaded.len() == 2);
+        assert!(loaded.get_value::<u32>("task::int").expect("must be u32") == 42);
+        assert!(loaded.get_value::<f32>("task::float").expect("must be f32") == 1.5);
+        assert!(loaded.get_value::<String>("task::unregistered").is_err());
+    }
+
+    /// counts the writes, to check records are written as they are encoded
+    struct CountingWriter {
+        data: Vec<u8>,
+        writes: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.data.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let codec = get_test_codec();
+        let mut cache = ValuesCache::new();
+        for i in 0..100u32 {
+            cache.save_value_str(&format!("task{}::int", i), i);
+            cache.save_value_str(&format!("task{}::float", i), i as f32 / 2.0);
+        }
+
+        let mut out = CountingWriter { data: Vec::new(), writes: 0 };
+        assert!(stream_dump(&cache, &mut out, &codec).expect("could not write") == 200);
+        assert!(out.writes >= 200);
+
+        let loaded = stream_load(&mut out.data.as_slice(), &codec).expect("could not read");
+        assert!(loaded.len() == cache.len());
+        for i in 0..100u32 {
+            assert!(loaded.get_value::<u32>(&format!("task{}::int", i)).expect("must be u32") == i);
+            assert!(loaded.get_value::<f32>(&format!("task{}::float", i)).expect("must be f32") == i as f32 / 2.0);
+        }
+
+        // truncated input
+        let short = &out.data[..out.data.len() - 2];
+        assert!(stream_load(&mut &short[..], &codec).is_err());
+
+        // a length larger than the input is not allocated upfront
+        let huge = [0xffu8, 0xff, 0xff, 0xff, b'u'];
+        let err = stream_load(&mut &huge[..], &codec).unwrap_err();
+        assert!(err.kind() == io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn truncated_length_prefix() {
+        let codec = get_test_codec();
+        let mut cache = ValuesCache::new();
+        cache.save_value_str("task::int", 42u32);
+
+        let mut data = Vec::new();
+        stream_dump(&cache, &mut data, &codec).expect("could not write");
+        assert!(stream_load(&mut data.as_slice(), &codec).expect("could not read").len() == 1);
+
+        // inside the length of the name, after the tag "u32"
+        let err = stream_load(&mut &data[..4 + 3 + 2], &codec).unwrap_err();
+        assert!(err.kind() == io::ErrorKind::UnexpectedEof);
+
+        // inside the length of the next record
+        data.extend_from_slice(&[3, 0]);
+        let err = stream_load(&mut data.as_slice(), &codec).unwrap_err();
+        assert!(err.kind() == io::ErrorKind::UnexpectedEof);
+    }
+}
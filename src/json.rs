@@ -0,0 +1,478 @@
+//! Hand written JSON representation of the graph wiring.
+//!
+//! The layout is stable, so it can be consumed and diffed by external tools:
+//!
+//! ```text
+//! {
+//!   "nodes": [ { "name": "task", "ins": ["task::a"], "outs": ["task::b"],
+//!                "description": "what it does", "metadata": { "group": "math" } } ],
+//!   "bindings": [ { "src": "other::b", "sink": "task::a" } ],
+//!   "conditional_bindings": [ { "src": "other::b", "sink": "task::a", "flag": "f::on" } ],
+//!   "freestanding": [ { "name": "start", "type": "u32" } ]
+//! }
+//! ```
+//!
+//! Entries are sorted by name. The description and metadata are only written for nodes
+//! which have them (see `Node::with_description` and `Graph::set_metadata`).
+//! Freestanding assets are listed apart, not as nodes. Type names are the ones reported
+//! by `std::any::type_name`, which may change between compilers, so they are
+//! informative only.
+//!
+//! The same layout can be applied to a graph (see `Graph::apply_wiring_json`), so the
+//! topology can be stored apart from the tasks implementation.
+
+use super::*;
+
+use std::fmt::Write;
+
+impl Graph {
+    /// Exports the topology of the graph in JSON, see the `json` module for the layout.
+    /// Task implementations are not part of the output.
+    pub fn export_json(&self) -> String {
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|(name, _)| !self.freestanding_types.contains_key(*name))
+            .map(|(name, node)| {
+                let description = match node.get_description() {
+                    Some(description) => format!(", \"description\": {}", quote(description)),
+                    None => String::new(),
+                };
+                let metadata = match self.metadata.get(name) {
+                    Some(entries) => {
+                        let entries: Vec<String> = entries
+                            .iter()
+                            .map(|(key, value)| format!("{}: {}", quote(key), quote(value)))
+                            .collect();
+                        format!(", \"metadata\": {{ {} }}", entries.join(", "))
+                    }
+                    None => String::new(),
+                };
+                format!(
+                    "{{ \"name\": {}, \"ins\": {}, \"outs\": {}{}{} }}",
+                    quote(name),
+                    list(node.get_ins()),
+                    list(node.get_outs()),
+                    description,
+                    metadata
+                )
+            })
+            .collect();
+
+        let bindings: Vec<String> = self
+            .bindings
+            .iter()
+            .map(|(sink, src)| format!("{{ \"src\": {}, \"sink\": {} }}", quote(src), quote(sink)))
+            .collect();
+
+        let conditional: Vec<String> = self
+            .conditional_bindings
+            .iter()
+            .flat_map(|(sink, entries)| {
+                entries.iter().map(move |(src, flag)| {
+                    format!(
+                        "{{ \"src\": {}, \"sink\": {}, \"flag\": {} }}",
+                        quote(src),
+                        quote(sink),
+                        quote(flag)
+                    )
+                })
+            })
+            .collect();
+
+        let freestanding: Vec<String> = self
+            .freestanding_types
+            .iter()
+            .map(|(name, ty)| format!("{{ \"name\": {}, \"type\": {} }}", quote(name), quote(ty)))
+            .collect();
+
+        let mut out = String::from("{\n");
+        for (i, (key, entries)) in [
+            ("nodes", nodes),
+            ("bindings", bindings),
+            ("conditional_bindings", conditional),
+            ("freestanding", freestanding),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if i > 0 {
+                out.push_str(",\n");
+            }
+            if entries.is_empty() {
+                write!(out, "  \"{}\": []", key).unwrap();
+            } else {
+                write!(out, "  \"{}\": [\n    {}\n  ]", key, entries.join(",\n    ")).unwrap();
+            }
+        }
+        out.push_str("\n}\n");
+        out
+    }
+}
+
+impl Graph {
+    /// Applies the bindings described in a JSON document, as written by `export_json`, to a
+    /// graph already populated with the tasks. Values of freestanding assets are not part of
+    /// the description, they must be declared beforehand. Their type in the description is
+    /// not checked, bindings are type checked as usual.
+    /// Nodes in the description must exist with the same assets, otherwise
+    /// `GraphError::NodeNotFound` or `GraphError::SignatureMismatch` is returned.
+    /// If any binding fails, the bindings of the graph are left untouched.
+    pub fn apply_wiring_json(&mut self, json: &str) -> Result<(), GraphError> {
+        let doc = Parser::new(json).document()?;
+
+        for node in doc.array("nodes")? {
+            let name = node.string("name")?;
+            let existing = match self.get_node(name) {
+                Some(existing) => existing,
+                None => return Err(GraphError::NodeNotFound(name.into())),
+            };
+            if node.strings("ins")? != existing.get_ins() || node.strings("outs")? != existing.get_outs() {
+                return Err(GraphError::SignatureMismatch(name.into()));
+            }
+        }
+
+        for asset in doc.array("freestanding")? {
+            let name = asset.string("name")?;
+            if !self.freestanding_types.contains_key(name) {
+                return Err(GraphError::UndefinedAssetSlot(name.into()));
+            }
+        }
+
+        let bindings = self.bindings.clone();
+        let conditional_bindings = self.conditional_bindings.clone();
+        let res = self.apply_bindings(&doc);
+        if res.is_err() {
+            self.bindings = bindings;
+            self.conditional_bindings = conditional_bindings;
+        }
+        res
+    }
+
+    fn apply_bindings(&mut self, doc: &Value) -> Result<(), GraphError> {
+        for binding in doc.array("bindings")? {
+            self.bind_asset(binding.string("src")?, binding.string("sink")?)?;
+        }
+        for binding in doc.array("conditional_bindings")? {
+            self.bind_conditional(
+                binding.string("src")?,
+                binding.string("sink")?,
+                binding.string("flag")?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The subset of JSON needed to read the wiring, numbers are not used
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Result<&Value, GraphError> {
+        if let Value::Object(entries) = self {
+            if let Some((_, value)) = entries.iter().find(|(k, _)| k == key) {
+                return Ok(value);
+            }
+        }
+        Err(invalid(format!("missing key \"{}\"", key)))
+    }
+
+    fn array(&self, key: &str) -> Result<&[Value], GraphError> {
+        match self.get(key)? {
+            Value::Array(items) => Ok(items),
+            _ => Err(invalid(format!("\"{}\" must be an array", key))),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<&str, GraphError> {
+        match self.get(key)? {
+            Value::Str(text) => Ok(text),
+            _ => Err(invalid(format!("\"{}\" must be a string", key))),
+        }
+    }
+
+    fn strings(&self, key: &str) -> Result<Vec<String>, GraphError> {
+        self.array(key)?
+            .iter()
+            .map(|item| match item {
+                Value::Str(text) => Ok(text.clone()),
+                _ => Err(invalid(format!("\"{}\" must contain strings", key))),
+            })
+            .collect()
+    }
+}
+
+fn invalid(reason: String) -> GraphError {
+    GraphError::InvalidWiring(reason)
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Parser<'a> {
+        Parser {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    /// a single value, nothing but whitespace may follow it
+    fn document(&mut self) -> Result<Value, GraphError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(value),
+            Some(c) => Err(invalid(format!("unexpected '{}' after the document", c))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, GraphError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::Str),
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('n') => self.keyword("null", Value::Null),
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.peek() {
+                    if !(c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                        break;
+                    }
+                    number.push(*c);
+                    self.chars.next();
+                }
+                Ok(Value::Number(number))
+            }
+            Some(c) => Err(invalid(format!("unexpected '{}'", c))),
+            None => Err(invalid("unexpected end of input".into())),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, GraphError> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Ok(Value::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(entries)),
+                _ => return Err(invalid("expected ',' or '}' in object".into())),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, GraphError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(items)),
+                _ => return Err(invalid("expected ',' or ']' in array".into())),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, GraphError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(out),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('u') => {
+                        let code: String = self.chars.by_ref().take(4).collect();
+                        let c = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                            .ok_or_else(|| invalid(format!("invalid escape \\u{}", code)))?;
+                        out.push(c);
+                    }
+                    Some(c) => out.push(c),
+                    None => return Err(invalid("unterminated string".into())),
+                },
+                Some(c) => out.push(c),
+                None => return Err(invalid("unterminated string".into())),
+            }
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, GraphError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), GraphError> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(invalid(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(invalid(format!("expected '{}', found the end of input", expected))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+}
+
+fn list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| quote(item)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn quote(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn export() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(add ( a : u32, b : u32) -> (sum : u32)
+                                 { sum = a + b; }))
+            .unwrap();
+        g.add_node(create_node!(gen () -> (value : u32)
+                                 { value = 1; }))
+            .unwrap();
+        g.define_freestanding_asset("start", 40u32).expect("redeclared?");
+        g.bind_asset("start", "add::a").expect("binding must be doable");
+        g.bind_asset("gen::value", "add::b").expect("binding must be doable");
+
+        let json = g.export_json();
+        println!("{}", json);
+
+        for key in &["\"nodes\"", "\"bindings\"", "\"conditional_bindings\"", "\"freestanding\""] {
+            assert!(json.contains(key));
+        }
+        assert!(json.matches("\"sink\"").count() == 2);
+        assert!(json.contains("{ \"src\": \"start::value\", \"sink\": \"add::a\" }"));
+        assert!(json.contains("{ \"name\": \"add\", \"ins\": [\"add::a\", \"add::b\"], \"outs\": [\"add::sum\"] }"));
+        assert!(json.contains("{ \"name\": \"start\", \"type\": \"u32\" }"));
+        assert!(!json.contains("{ \"name\": \"start\", \"ins\""));
+        assert!(json == g.export_json());
+
+        assert!(quote("a\"b\\c") == "\"a\\\"b\\\\c\"");
+    }
+
+    fn get_unwired_graph() -> Graph {
+        let mut g = Graph::new();
+        g.add_node(create_node!(add ( a : u32, b : u32) -> (sum : u32)
+                                 { sum = a + b; }))
+            .unwrap();
+        g.add_node(create_node!(gen () -> (value : u32)
+                                 { value = 2; }))
+            .unwrap();
+        g.define_freestanding_asset("start", 40u32).expect("redeclared?");
+        g
+    }
+
+    #[test]
+    fn apply_wiring() {
+        let mut wired = get_unwired_graph();
+        wired.bind_asset("start", "add::a").expect("binding must be doable");
+        wired.bind_asset("gen::value", "add::b").expect("binding must be doable");
+        let json = wired.export_json();
+
+        let mut g = get_unwired_graph();
+        g.apply_wiring_json(&json).expect("wiring must apply");
+        assert!(g.topology_eq(&wired));
+        assert!(g.export_json() == json);
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("add").expect("could not execute");
+        assert!(solver.get_value::<u32>("add::sum").expect("must be u32") == 42);
+    }
+
+    #[test]
+    fn apply_wiring_errors() {
+        let mut g = get_unwired_graph();
+
+        assert!(matches!(g.apply_wiring_json("{ \"nodes\": [ }"),
+                         Err(GraphError::InvalidWiring(_))));
+        assert!(matches!(g.apply_wiring_json("{ \"nodes\": [] }"),
+                         Err(GraphError::InvalidWiring(_))));
+
+        let missing = r#"{ "nodes": [ { "name": "nop", "ins": [], "outs": [] } ],
+                           "bindings": [], "conditional_bindings": [], "freestanding": [] }"#;
+        assert!(matches!(g.apply_wiring_json(missing), Err(GraphError::NodeNotFound(_))));
+
+        let undeclared = r#"{ "nodes": [], "bindings": [], "conditional_bindings": [],
+                              "freestanding": [ { "name": "other", "type": "u32" } ] }"#;
+        assert!(matches!(g.apply_wiring_json(undeclared), Err(GraphError::UndefinedAssetSlot(_))));
+
+        // type names differ between compilers, only bindings are type checked
+        let retyped = r#"{ "nodes": [], "conditional_bindings": [],
+                           "freestanding": [ { "name": "start", "type": "other::u32" } ],
+                           "bindings": [ { "src": "start::value", "sink": "add::a" } ] }"#;
+        g.apply_wiring_json(retyped).expect("type names are advisory");
+        assert!(g.get_binding_str("add::a").is_some());
+
+        // the second binding fails, the first one is not kept
+        let broken = r#"{ "nodes": [], "conditional_bindings": [], "freestanding": [],
+                          "bindings": [ { "src": "gen::value", "sink": "add::b" },
+                                        { "src": "gen::nothing", "sink": "add::a" } ] }"#;
+        assert!(matches!(g.apply_wiring_json(broken), Err(GraphError::UndefinedAssetSlot(_))));
+        assert!(g.get_binding_str("add::b").is_none());
+    }
+}
@@ -28,11 +28,11 @@
 //!    to retrieve the values.
 //! 1. Solve the graph: there are currently two methods to solve a graph: 
 //!    - execute: where the parameter is the name of the task we want to execute. Prerequisites
-//!    will be identified and executed, if not possible because the topology is ill formed, an
-//!    error will be returned.
+//!      will be identified and executed, if not possible because the topology is ill formed, an
+//!      error will be returned.
 //!    - execute_terminals: terminal tasks are those with no outputs. Any number of terminal tasks
-//!    can be defined, all of them will be executed if prerequistes can be satisfied, otherwise an
-//!    error will be returned.
+//!      can be defined, all of them will be executed if prerequistes can be satisfied, otherwise an
+//!      error will be returned.
 //! 
 //! ### Use by example
 //! 
@@ -59,12 +59,13 @@
 //!
 //! The body of the task will be executed by a move lambda, this enforces some guarantees.
 //! Nevertheless if the tasks need to execute some side effects, you may keep in mind that:
-//! - Objects need to be cloned into the task scope.
+//! - Objects need to be cloned into the task scope, the capture list syntax
+//!   `create_node!(task [shared: Rc<T>] (...) -> (...) { ... })` does this for you.
 //! - Only runtime borrowing can be checked at this point.
 //! - The Solver has no knowledge of data changes done via global access. It only tracks assets
-//! registered as inputs or outputs of the task. For this reason tasks may not be executed a second
-//! time as long as the inputs do not change. This may turn into side effects not happening because
-//! the requirements were not declared correctly.  
+//!   registered as inputs or outputs of the task. For this reason tasks may not be executed a second
+//!   time as long as the inputs do not change. This may turn into side effects not happening because
+//!   the requirements were not declared correctly.
 //!
 //! Once the tasks are defined, you can bind the input assets to the output produced by other task
 //! or feed directly into the Solver.
@@ -125,15 +126,19 @@
 extern crate dot;
 // extern crate test;
 
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::cmp;
 use std::collections::BTreeMap as Map;
 use std::mem;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use std::vec::Vec;
 
 #[macro_use]
 mod macros;
+pub mod cache;
+pub mod json;
 pub mod printer;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -145,6 +150,25 @@ pub trait NodeRunner {
     fn run(&self, solver: &mut GraphSolver) -> Result<SolverStatus, SolverError>;
     fn get_ins(&self) -> &[String];
     fn get_outs(&self) -> &[String];
+    /// type of one of the input or output assets of the task, if known
+    fn get_asset_type(&self, asset: &str) -> Option<TypeId>;
+    /// tasks whose outputs are all consumed by this one, see `GraphSolver::outputs_of`
+    fn get_producers(&self) -> &[String] {
+        &[]
+    }
+    /// assets read from the previous solve, see `GraphSolver::feedback_input`
+    fn get_feedback(&self) -> &[String] {
+        &[]
+    }
+    /// whether the task reuses its cached outputs while its inputs do not change, as the
+    /// tasks created by `create_node` do. Only those may be skipped by the solver.
+    fn is_cache_aware(&self) -> bool {
+        false
+    }
+    /// human readable description of the task, if any
+    fn get_description(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Generic that stores the information required to execute arbitrary tasks
@@ -157,6 +181,12 @@ where
     func: F,
     ins: Vec<String>,
     outs: Vec<String>,
+    in_types: Vec<TypeId>,
+    out_types: Vec<TypeId>,
+    producers: Vec<String>,
+    feedback: Vec<String>,
+    description: Option<String>,
+    cache_aware: bool,
 }
 
 impl<F> Node<F>
@@ -169,11 +199,56 @@ where
     {
         Node {
             name: name.into(),
-            func: func,
-            ins: ins,
-            outs: outs,
+            func,
+            ins,
+            outs,
+            in_types: Vec::new(),
+            out_types: Vec::new(),
+            producers: Vec::new(),
+            feedback: Vec::new(),
+            description: None,
+            cache_aware: false,
         }
     }
+
+    /// attaches a human readable description, shown by the DOT and JSON exports. The
+    /// `create_node` macro takes it from doc comments before the task name.
+    pub fn with_description<S>(mut self, description: S) -> Node<F>
+    where
+        S: Into<String>,
+    {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// declares tasks whose outputs are all consumed by this one, they will be executed
+    /// before this task.
+    pub fn with_producers(mut self, producers: Vec<String>) -> Node<F> {
+        self.producers = producers;
+        self
+    }
+
+    /// declares the assets read from the previous solve, the task depends on them and is
+    /// never served from the cache.
+    pub fn with_feedback(mut self, feedback: Vec<String>) -> Node<F> {
+        self.feedback = feedback;
+        self
+    }
+
+    /// declares that the task reuses its cached outputs when its inputs did not change,
+    /// see `GraphSolver::use_old_ouput`. `create_node` macro does this for you.
+    pub fn cache_aware(mut self) -> Node<F> {
+        self.cache_aware = true;
+        self
+    }
+
+    /// records the types of the input and output assets, in declaration order.
+    /// `create_node` macro does this for you.
+    pub fn with_types(mut self, in_types: Vec<TypeId>, out_types: Vec<TypeId>) -> Node<F> {
+        self.in_types = in_types;
+        self.out_types = out_types;
+        self
+    }
 }
 
 impl<F> NodeRunner for Node<F>
@@ -192,8 +267,41 @@ where
     fn get_outs(&self) -> &[String] {
         &self.outs
     }
+    fn get_asset_type(&self, asset: &str) -> Option<TypeId> {
+        let find = |names: &[String], types: &[TypeId]| {
+            names
+                .iter()
+                .zip(types.iter())
+                .find(|(name, _)| name.as_str() == asset)
+                .map(|(_, t)| *t)
+        };
+        find(&self.ins, &self.in_types).or_else(|| find(&self.outs, &self.out_types))
+    }
+    fn get_producers(&self) -> &[String] {
+        &self.producers
+    }
+    fn get_feedback(&self) -> &[String] {
+        &self.feedback
+    }
+    fn is_cache_aware(&self) -> bool {
+        self.cache_aware
+    }
+    fn get_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
+/// Used by `create_node` on every output type: values saved in the cache can not borrow,
+/// an output such as `&'a str` fails here, next to the declaration of the task.
+///
+/// ```compile_fail
+/// fn borrowing<'a>(_text: &'a str) {
+///     rgraph::output_must_be_static::<&'a str>();
+/// }
+/// ```
+#[doc(hidden)]
+pub fn output_must_be_static<T: 'static>() {}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
@@ -201,16 +309,13 @@ where
 /// freestanding asset as well.
 pub enum AssetProvider<'a>{
     None,
-    Node(&'a NodeRunner),
+    Node(&'a dyn NodeRunner),
     Preset(&'a String)
 }
 
 impl<'a> AssetProvider<'a>{
     pub fn is_none(&'a self) -> bool{
-        match self{
-            AssetProvider::None => true,
-            _ => false,
-        }
+        matches!(self, AssetProvider::None)
     }
 }
 
@@ -219,8 +324,154 @@ impl<'a> AssetProvider<'a>{
 pub enum GraphError {
     UndefinedAssetSlot(String),
     RedefinedNode(String),
+    NodeNotFound(String),
     DisconnectedDependency,
     RedeclaredAsset(String),
+    /// The type of the source asset (first) does not match the type of the sink (second).
+    /// For default values, the source is the type of the value given.
+    TypeMismatch(String, String),
+    /// Following the bindings from this node leads back to it
+    CycleDetected(String),
+    /// The output asset is already produced by a different node
+    DuplicateOutput(String),
+    /// More than one output matches the type of the input asset
+    AmbiguousAutobind(String),
+    /// The replacement node does not declare the same assets as the replaced one
+    SignatureMismatch(String),
+    /// The wiring description can not be read, the reason is attached
+    InvalidWiring(String),
+    /// One of a batch of bindings failed, `index` is its position in the batch
+    BindingFailed {
+        index: usize,
+        source: Box<GraphError>,
+    },
+    /// The graph has no terminal nodes (no output), nothing is known to be needed
+    NoTerminals,
+}
+
+/// Order in which the nodes of a graph are listed, terminals are executed in this order
+/// by `GraphSolver::execute_terminals`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NodeOrdering {
+    /// sorted by node name
+    #[default]
+    Lexicographic,
+    /// in the order nodes were added to the graph
+    Insertion,
+}
+
+/// Outcome of a binding, see `Graph::bind_asset_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingInfo {
+    /// the asset bound to the sink, freestanding assets are provided by `name::value`
+    pub resolved_source: String,
+    /// whether the source is a freestanding asset
+    pub is_freestanding: bool,
+}
+
+/// Structural differences between two graphs, see `Graph::diff`. Entries are sorted,
+/// bindings are (source, sink) pairs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    pub added_bindings: Vec<(String, String)>,
+    pub removed_bindings: Vec<(String, String)>,
+    pub added_freestanding: Vec<String>,
+    pub removed_freestanding: Vec<String>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == GraphDiff::default()
+    }
+}
+
+/// Role of an asset within the graph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssetRole {
+    Input,
+    Output,
+    Freestanding,
+}
+
+/// Describes one of the assets declared in the graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetDescriptor {
+    /// fully qualified name of the asset
+    pub name: String,
+    /// name of the node declaring the asset
+    pub owner: String,
+    pub role: AssetRole,
+}
+
+/// Storage of the nodes in the graph, nodes are owned by the graph unless they were
+/// added as shared.
+enum NodeStorage {
+    Owned(Box<dyn NodeRunner>),
+    Shared(Rc<dyn NodeRunner>),
+}
+
+impl AsRef<dyn NodeRunner> for NodeStorage {
+    fn as_ref(&self) -> &(dyn NodeRunner + 'static) {
+        match self {
+            NodeStorage::Owned(node) => node.as_ref(),
+            NodeStorage::Shared(node) => node.as_ref(),
+        }
+    }
+}
+
+impl std::ops::Deref for NodeStorage {
+    type Target = dyn NodeRunner;
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+/// A node whose assets were renamed by `Graph::rename_assets`. The task still uses the
+/// names it declared, the solver translates them (see `Graph::current_name`).
+struct RenamedNode {
+    inner: NodeStorage,
+    ins: Vec<String>,
+    outs: Vec<String>,
+}
+
+impl NodeRunner for RenamedNode {
+    fn get_name(&self) -> &str {
+        self.inner.get_name()
+    }
+    fn run(&self, solver: &mut GraphSolver) -> Result<SolverStatus, SolverError> {
+        self.inner.run(solver)
+    }
+    fn get_ins(&self) -> &[String] {
+        &self.ins
+    }
+    fn get_outs(&self) -> &[String] {
+        &self.outs
+    }
+    fn get_asset_type(&self, asset: &str) -> Option<TypeId> {
+        let declared = |names: &[String], inner: &[String]| {
+            names
+                .iter()
+                .position(|name| name == asset)
+                .map(|i| inner[i].clone())
+        };
+        declared(&self.ins, self.inner.get_ins())
+            .or_else(|| declared(&self.outs, self.inner.get_outs()))
+            .and_then(|name| self.inner.get_asset_type(&name))
+    }
+    fn get_producers(&self) -> &[String] {
+        self.inner.get_producers()
+    }
+    fn get_feedback(&self) -> &[String] {
+        self.inner.get_feedback()
+    }
+    fn is_cache_aware(&self) -> bool {
+        self.inner.is_cache_aware()
+    }
+    fn get_description(&self) -> Option<&str> {
+        self.inner.get_description()
+    }
 }
 
 /// The graph class itself.
@@ -228,11 +479,23 @@ pub enum GraphError {
 /// depend on each other by waiting on resources (Assets)
 #[derive(Default)]
 pub struct Graph {
-    nodes: Map<String, Rc<NodeRunner>>,
-    terminals: Vec<Rc<NodeRunner>>,
-    whatprovides: Map<String, Rc<NodeRunner>>,
+    nodes: Map<String, NodeStorage>,
+    terminals: Vec<String>,
+    whatprovides: Map<String, String>,
     bindings: Map<String, String>,
     freestanding_assets: Vec<String>,
+    freestanding_types: Map<String, &'static str>,
+    costs: Map<String, u32>,
+    disabled: Vec<String>,
+    conditional_bindings: Map<String, Vec<(String, String)>>,
+    retries: Map<String, u32>,
+    aliases: Map<String, String>,
+    ordering: NodeOrdering,
+    insertion_order: Vec<String>,
+    defaults: Map<String, Rc<dyn Any>>,
+    metadata: Map<String, Map<String, String>>,
+    strict: bool,
+    renamed: Map<String, String>,
 }
 
 impl Graph {
@@ -242,35 +505,337 @@ impl Graph {
         }
     }
 
-    pub fn add_node<F: 'static>(&mut self, node: Node<F>) -> Result<(), GraphError>
+    /// Creates a graph in strict mode, see `set_strict`.
+    pub fn strict() -> Graph {
+        Graph {
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    /// In strict mode the checks of `finalize` run on every `add_node`, and `bind_asset`
+    /// checks the types of source and sink when both are known. Errors are reported
+    /// right away, at the cost of slower construction.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Adds a node, owned by the graph.
+    pub fn add_node<F>(&mut self, node: Node<F>) -> Result<(), GraphError>
+    where
+        F: 'static + Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>,
+    {
+        self.insert_node(NodeStorage::Owned(Box::new(node)))
+    }
+
+    /// Adds a node and returns the graph, to chain the construction:
+    ///
+    /// ```
+    /// use rgraph::*;
+    ///
+    /// # fn main() -> Result<(), GraphError> {
+    /// let g = Graph::new()
+    ///     .with_node(create_node!(gen () -> (x: u32) { x = 1; }))?
+    ///     .with_node(create_node!(double (x: u32) -> (y: u32) { y = x * 2; }))?;
+    /// assert!(g.node_count() == 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_node<F>(mut self, node: Node<F>) -> Result<Graph, GraphError>
+    where
+        F: 'static + Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>,
+    {
+        self.add_node(node)?;
+        Ok(self)
+    }
+
+    /// Wraps the graph in a single node, so it can be used as a task of another graph. The
+    /// node declares the given inputs and outputs of the subgraph, named `name::asset`
+    /// after the part of the subgraph asset name following `::`. When run, the inputs are
+    /// fed to a nested solver, the producers of the outputs are executed and the outputs
+    /// copied back. The nested cache is kept between runs, as for any other solve.
+    /// Assets which would get the same outer name (i.e. `a::x` and `b::x`) are rejected
+    /// with `GraphError::RedeclaredAsset`.
+    pub fn into_node(
+        self,
+        name: &str,
+        inputs: &[&str],
+        outputs: &[&str],
+    ) -> Result<Node<impl Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>>, GraphError> {
+        let outer = |asset: &str| format!("{}::{}", name, asset.rsplit("::").next().unwrap_or(asset));
+        let ins: Vec<(String, String)> = inputs.iter().map(|a| (outer(a), a.to_string())).collect();
+        let outs: Vec<(String, String)> = outputs.iter().map(|a| (outer(a), a.to_string())).collect();
+
+        let mut names = std::collections::BTreeSet::new();
+        if let Some((outer, _)) = ins.iter().chain(outs.iter()).find(|(outer, _)| !names.insert(outer)) {
+            return Err(GraphError::RedeclaredAsset(outer.clone()));
+        }
+
+        let types = |assets: &[&str]| -> Option<Vec<TypeId>> {
+            assets.iter().map(|asset| self.asset_type(asset)).collect()
+        };
+        let (in_types, out_types) = (types(inputs), types(outputs));
+
+        let node_ins = ins.iter().map(|(outer, _)| outer.clone()).collect();
+        let node_outs = outs.iter().map(|(outer, _)| outer.clone()).collect();
+        let graph = self;
+        let last_cache = std::cell::RefCell::new(ValuesCache::new());
+        let node = Node::new(
+            name,
+            move |solver: &mut GraphSolver| {
+                let mut last_cache = last_cache.borrow_mut();
+                let mut nested = GraphSolver::new(&graph, &mut last_cache);
+                for (outer, inner) in &ins {
+                    let value = match solver.get_raw(solver.input_source(outer)) {
+                        Some(value) => value,
+                        None => return Err(SolverError::AssetNotCreated(outer.clone())),
+                    };
+                    let src = nested.input_source(inner).to_string();
+                    nested.save_raw(&src, value);
+                }
+                for (_, inner) in &outs {
+                    match graph.provider_of(inner) {
+                        Some(producer) => nested.execute(producer.get_name())?,
+                        None => return Err(SolverError::AssetNotProduced(inner.clone())),
+                    };
+                }
+                for (outer, inner) in &outs {
+                    match nested.get_raw(inner) {
+                        Some(value) => solver.save_raw(outer, value),
+                        None => return Err(SolverError::AssetNotCreated(inner.clone())),
+                    }
+                }
+                Ok(SolverStatus::Executed)
+            },
+            node_ins,
+            node_outs,
+        );
+        match (in_types, out_types) {
+            (Some(in_types), Some(out_types)) => Ok(node.with_types(in_types, out_types)),
+            _ => Ok(node),
+        }
+    }
+
+    /// Adds a sequence of nodes, binding the output of each one to the input of the next.
+    /// Every node must have a single output and, but for the first one, a single input;
+    /// otherwise `GraphError::SignatureMismatch` is returned. See the `pipeline!` macro.
+    pub fn add_pipeline(&mut self, stages: Vec<Box<dyn NodeRunner>>) -> Result<(), GraphError> {
+        for (i, stage) in stages.iter().enumerate() {
+            if stage.get_outs().len() != 1 || (i > 0 && stage.get_ins().len() != 1) {
+                return Err(GraphError::SignatureMismatch(stage.get_name().into()));
+            }
+        }
+        for pair in stages.windows(2) {
+            let (src, sink) = (&pair[0].get_outs()[0], &pair[1].get_ins()[0]);
+            match (pair[0].get_asset_type(src), pair[1].get_asset_type(sink)) {
+                (Some(a), Some(b)) if a != b => {
+                    return Err(GraphError::TypeMismatch(src.clone(), sink.clone()))
+                }
+                _ => {}
+            }
+        }
+
+        let mut previous: Option<String> = None;
+        for stage in stages {
+            let input = stage.get_ins().first().cloned();
+            let output = stage.get_outs()[0].clone();
+            self.insert_node(NodeStorage::Owned(stage))?;
+            if let (Some(src), Some(sink)) = (previous, input) {
+                self.bind_asset(&src, &sink)?;
+            }
+            previous = Some(output);
+        }
+        Ok(())
+    }
+
+    /// Adds a node which may be shared with other graphs.
+    pub fn add_shared_node(&mut self, node: Rc<dyn NodeRunner>) -> Result<(), GraphError> {
+        self.insert_node(NodeStorage::Shared(node))
+    }
+
+    /// Swaps the implementation of a node, keeping all its bindings. The new node must have
+    /// the same name and declare the same input and output assets.
+    /// Note that outputs cached by the old implementation are still valid for the solver
+    /// while the inputs of the node do not change.
+    pub fn replace_node<F>(&mut self, node: Node<F>) -> Result<(), GraphError>
     where
-        F: Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>,
+        F: 'static + Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>,
     {
-        let newnode = Rc::new(node);
-        let name: String = newnode.as_ref().get_name().into();
+        let name: String = node.get_name().into();
+        let old = match self.nodes.get(&name) {
+            Some(old) => old,
+            None => return Err(GraphError::NodeNotFound(name)),
+        };
+
+        let same_signature = old.get_ins() == node.get_ins()
+            && old.get_outs() == node.get_outs()
+            && old
+                .get_ins()
+                .iter()
+                .chain(old.get_outs())
+                .all(|asset| old.get_asset_type(asset) == node.get_asset_type(asset));
+        if !same_signature {
+            return Err(GraphError::SignatureMismatch(name));
+        }
+
+        self.nodes.insert(name, NodeStorage::Owned(Box::new(node)));
+        Ok(())
+    }
+
+    /// Removes a node, together with all the bindings to and from its assets and any
+    /// setting attached to it.
+    pub fn remove_node(&mut self, name: &str) -> Result<(), GraphError> {
+        let node = match self.nodes.remove(name) {
+            Some(node) => node,
+            None => return Err(GraphError::NodeNotFound(name.into())),
+        };
+        let involved = |asset: &String| node.get_ins().contains(asset) || node.get_outs().contains(asset);
+
+        self.bindings.retain(|sink, src| !involved(sink) && !involved(src));
+        self.conditional_bindings.retain(|sink, _| !involved(sink));
+        for entries in self.conditional_bindings.values_mut() {
+            entries.retain(|(src, flag)| !involved(src) && !involved(flag));
+        }
+        self.conditional_bindings.retain(|_, entries| !entries.is_empty());
+        self.aliases.retain(|_, existing| !involved(existing));
+        self.defaults.retain(|input, _| !involved(input));
+
+        self.whatprovides.retain(|_, provider| provider != name);
+        self.terminals.retain(|terminal| terminal != name);
+        self.freestanding_assets.retain(|asset| asset != name);
+        self.freestanding_types.remove(name);
+        self.costs.remove(name);
+        self.disabled.retain(|disabled| disabled != name);
+        self.retries.remove(name);
+        self.metadata.remove(name);
+        self.insertion_order.retain(|node| node != name);
+        Ok(())
+    }
+
+    /// Removes all the nodes which do not contribute to any terminal, freestanding assets
+    /// included. Returns the names of the removed nodes. A graph without terminals is left
+    /// untouched and `GraphError::NoTerminals` is returned, instead of removing every node.
+    pub fn prune(&mut self) -> Result<Vec<String>, GraphError> {
+        if self.terminals.is_empty() {
+            return Err(GraphError::NoTerminals);
+        }
+        let needed: Vec<&str> = self
+            .dependency_cone(self.get_terminals())
+            .iter()
+            .map(|node| node.get_name())
+            .collect();
+
+        let removed: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|name| !needed.contains(&name.as_str()))
+            .cloned()
+            .collect();
+        for name in &removed {
+            self.remove_node(name).expect("node exists");
+        }
+        Ok(removed)
+    }
+
+    fn insert_node(&mut self, newnode: NodeStorage) -> Result<(), GraphError> {
+        let name: String = newnode.get_name().into();
 
         if self.nodes.contains_key(&name) {
             return Err(GraphError::RedefinedNode(name));
         }
+        if let Some(out) = newnode
+            .get_outs()
+            .iter()
+            .find(|out| self.whatprovides.contains_key(*out))
+        {
+            return Err(GraphError::DuplicateOutput(out.clone()));
+        }
+        if self.strict {
+            let outs = newnode.get_outs();
+            if let Some(out) = outs.iter().enumerate().find(|(i, out)| outs[..*i].contains(out)) {
+                return Err(GraphError::DuplicateOutput(out.1.clone()));
+            }
+            if let Some(input) = newnode.get_ins().iter().find(|input| {
+                self.whatprovides.contains_key(*input) || outs.contains(input)
+            }) {
+                return Err(GraphError::RedeclaredAsset(input.clone()));
+            }
+            if let Some(out) = outs.iter().find(|out| {
+                self.nodes.values().any(|node| node.get_ins().contains(out))
+            }) {
+                return Err(GraphError::RedeclaredAsset(out.clone()));
+            }
+        }
 
-        for out in newnode.as_ref().get_outs() {
-            self.whatprovides.insert(out.clone(), newnode.clone());
+        for out in newnode.get_outs() {
+            self.whatprovides.insert(out.clone(), name.clone());
         }
-        if newnode.as_ref().get_outs().is_empty() {
-            self.terminals.push(newnode.clone());
+        if newnode.get_outs().is_empty() {
+            self.terminals.push(name.clone());
         }
+        self.insertion_order.push(name.clone());
 
         self.nodes.insert(name, newnode);
         Ok(())
     }
 
-    pub fn get_node(&self, name: &str) -> Option<&NodeRunner> {
-        let key: String = name.into();
-        self.nodes.get(&key).map(|res| res.as_ref())
+    pub fn get_node(&self, name: &str) -> Option<&dyn NodeRunner> {
+        self.nodes.get(name).map(|res| res.as_ref())
+    }
+
+    /// input assets of a node, None if there is no such node
+    pub fn inputs_of(&self, node: &str) -> Option<&[String]> {
+        self.get_node(node).map(|node| node.get_ins())
+    }
+
+    /// output assets of a node, None if there is no such node
+    pub fn outputs_of(&self, node: &str) -> Option<&[String]> {
+        self.get_node(node).map(|node| node.get_outs())
+    }
+
+    /// Nodes without outputs, listed according to the ordering of the graph
+    pub fn get_terminals(&self) -> Vec<&dyn NodeRunner> {
+        let mut terminals: Vec<&dyn NodeRunner> = self
+            .terminals
+            .iter()
+            .filter_map(|name| self.get_node(name))
+            .collect();
+        if self.ordering == NodeOrdering::Lexicographic {
+            terminals.sort_by_key(|node| node.get_name());
+        }
+        terminals
+    }
+
+    /// Names of the nodes without outputs, listed according to the ordering of the graph
+    pub fn terminal_names(&self) -> Vec<&str> {
+        self.get_terminals().iter().map(|node| node.get_name()).collect()
+    }
+
+    /// Sets the order in which nodes are listed, lexicographic by default.
+    pub fn set_ordering(&mut self, ordering: NodeOrdering) {
+        self.ordering = ordering;
+    }
+
+    pub fn get_ordering(&self) -> NodeOrdering {
+        self.ordering
+    }
+
+    /// node producing an output asset
+    fn provider_of(&self, asset: &str) -> Option<&dyn NodeRunner> {
+        self.whatprovides.get(asset).and_then(|name| self.get_node(name))
+    }
+
+    /// number of nodes in the graph, freestanding assets included
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn terminal_count(&self) -> usize {
+        self.terminals.len()
     }
 
-    pub fn get_terminals(&self) -> &[Rc<NodeRunner>] {
-        self.terminals.as_slice()
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
     }
 
     pub fn get_binding(&self, name: &String) -> Option<&String> {
@@ -281,71 +846,284 @@ impl Graph {
         self.bindings.get(name)
     }
 
+    /// Inputs bound directly to an output asset, the inverse of `get_binding`. Inputs bound
+    /// through another input are not listed. Freestanding assets can be given by name.
+    pub fn consumers_of_output(&self, output: &str) -> Vec<String> {
+        let output = self.qualify_source(output);
+        self.bindings
+            .iter()
+            .filter(|(_, src)| **src == output)
+            .map(|(sink, _)| sink.clone())
+            .collect()
+    }
+
+    /// Renames assets all over the graph: assets of the nodes, bindings and any setting
+    /// attached to an asset. `f` is called with every asset name and returns the new name,
+    /// or `None` to keep it. Freestanding assets keep their name, their `value` output may
+    /// be renamed.
+    /// Tasks keep using the names they declared, the solver translates them. Values cached
+    /// under the old names are not used anymore.
+    pub fn rename_assets(&mut self, f: impl Fn(&str) -> Option<String>) {
+        let mut step: Map<String, String> = Map::new();
+        for asset in self.nodes.values().flat_map(|node| node.get_ins().iter().chain(node.get_outs())) {
+            if let Some(new) = f(asset) {
+                if new != *asset {
+                    step.insert(asset.clone(), new);
+                }
+            }
+        }
+        if step.is_empty() {
+            return;
+        }
+        let rename = |asset: &String| step.get(asset).cloned().unwrap_or_else(|| asset.clone());
+
+        let nodes = mem::take(&mut self.nodes);
+        for (name, node) in nodes {
+            let ins: Vec<String> = node.get_ins().iter().map(rename).collect();
+            let outs: Vec<String> = node.get_outs().iter().map(rename).collect();
+            let node = if ins == node.get_ins() && outs == node.get_outs() {
+                node
+            } else {
+                NodeStorage::Owned(Box::new(RenamedNode { inner: node, ins, outs }))
+            };
+            self.nodes.insert(name, node);
+        }
+
+        self.whatprovides = mem::take(&mut self.whatprovides)
+            .into_iter()
+            .map(|(asset, node)| (rename(&asset), node))
+            .collect();
+        self.bindings = mem::take(&mut self.bindings)
+            .into_iter()
+            .map(|(sink, src)| (rename(&sink), rename(&src)))
+            .collect();
+        self.conditional_bindings = mem::take(&mut self.conditional_bindings)
+            .into_iter()
+            .map(|(sink, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(src, flag)| (rename(src), rename(flag)))
+                    .collect();
+                (rename(&sink), entries)
+            })
+            .collect();
+        for existing in self.aliases.values_mut() {
+            *existing = rename(existing);
+        }
+        self.defaults = mem::take(&mut self.defaults)
+            .into_iter()
+            .map(|(input, value)| (rename(&input), value))
+            .collect();
+
+        // names declared by the tasks, to the current ones
+        for current in self.renamed.values_mut() {
+            if let Some(new) = step.remove(current) {
+                *current = new;
+            }
+        }
+        self.renamed.extend(step);
+    }
+
+    /// current name of an asset as declared by its task, see `rename_assets`
+    fn current_name<'s>(&'s self, declared: &'s str) -> &'s str {
+        match self.renamed.get(declared) {
+            Some(current) => current.as_str(),
+            None => declared,
+        }
+    }
+
     /// declares and initializes a freestanding asset, this assets are defined as global inputs
     /// to the graph and can be used to feed initial values in the system
     pub fn define_freestanding_asset<T: 'static+Clone>(&mut self, name: &str, val :T)  -> Result<(), GraphError>{
 
 
         if self.freestanding_assets.iter()
-            .any(|name| name.as_str() == name)
+            .any(|declared| declared.as_str() == name)
         {
             return Err(GraphError::RedeclaredAsset(name.into()));
         }
-        self.freestanding_assets.push(name.into());
 
         let name : String = name.into();
         self.add_node(create_node!(name: name, () -> (value : T)
                                  {
                                      value = val.clone();
-                                 }))
+                                 }))?;
+        self.freestanding_types.insert(name.clone(), std::any::type_name::<T>());
+        self.freestanding_assets.push(name);
+        Ok(())
+    }
+
+    /// Declares a freestanding asset, like `define_freestanding_asset`, and returns the
+    /// qualified name of the asset providing its value (`name::value`), to be used as
+    /// source in bindings.
+    pub fn add_freestanding<T: 'static + Clone>(&mut self, name: &str, val: T) -> Result<String, GraphError> {
+        self.define_freestanding_asset(name, val)?;
+        Ok(self.qualify_source(name))
     }
 
     /// Binds two nodes. An asset satisfied by a task, will be the input for another task
     /// under a different asset name.
     /// One output asset can be used in one or more inputs.
+    /// An input which is already bound can be used as source as well, the sink will be
+    /// satisfied by the same output (see `resolve_source`).
     /// If the input is already bound, the link will be overwritten
     pub fn bind_asset(&mut self, src: &str, sink: &str) -> Result<(), GraphError> {
+        self.bind_asset_report(src, sink).map(|_| ())
+    }
+
+    /// Binds two assets, like `bind_asset`, reporting the source actually used.
+    pub fn bind_asset_report(&mut self, src: &str, sink: &str) -> Result<BindingInfo, GraphError> {
+        let src = self.check_binding(src, sink)?;
+        let is_freestanding = self
+            .freestanding_assets
+            .iter()
+            .any(|name| self.freestanding_source(name) == src);
+        self.bindings.insert(sink.into(), src.clone());
+        Ok(BindingInfo {
+            resolved_source: src,
+            is_freestanding,
+        })
+    }
+
+    /// Binds the output `src_output` of the node `src_node` to an input. The output may be
+    /// given by its short name (`value`) or fully qualified (`node::value`).
+    pub fn bind_from_node(&mut self, src_node: &str, src_output: &str, sink: &str) -> Result<(), GraphError> {
+        let node = match self.get_node(src_node) {
+            Some(node) => node,
+            None => return Err(GraphError::NodeNotFound(src_node.into())),
+        };
+        let qualified = format!("{}::{}", src_node, src_output);
+        let src = match node
+            .get_outs()
+            .iter()
+            .find(|out| out.as_str() == src_output || **out == qualified)
+        {
+            Some(src) => src.clone(),
+            None => return Err(GraphError::UndefinedAssetSlot(qualified)),
+        };
+        self.bind_asset(&src, sink)
+    }
+
+    /// Binds a batch of (source, sink) pairs, as `bind_asset` does. If any binding fails,
+    /// `GraphError::BindingFailed` reports its position and the bindings of the graph are
+    /// left untouched.
+    pub fn bind_pairs(&mut self, pairs: impl IntoIterator<Item = (String, String)>) -> Result<(), GraphError> {
+        let bindings = self.bindings.clone();
+        for (index, (src, sink)) in pairs.into_iter().enumerate() {
+            if let Err(e) = self.bind_asset(&src, &sink) {
+                self.bindings = bindings;
+                return Err(GraphError::BindingFailed {
+                    index,
+                    source: Box::new(e),
+                });
+            }
+        }
+        Ok(())
+    }
 
+    /// Binds an output to an input only while the boolean asset `flag` is true, the flag is
+    /// computed by the solver before the input is resolved. When the flag is false the
+    /// regular binding of the input is used, if any, otherwise the input is unbound.
+    /// Several conditional bindings may target the same input, the first one whose flag is
+    /// true is honored.
+    pub fn bind_conditional(&mut self, src: &str, sink: &str, flag: &str) -> Result<(), GraphError> {
+        let src = self.check_binding(src, sink)?;
+        let flag = self.qualify_source(flag);
         if !self.nodes
             .values()
-            .any(|node| node.get_ins().iter().any(|name| name.as_str() == sink))
+            .any(|node| node.get_outs().contains(&flag))
         {
-            return Err(GraphError::UndefinedAssetSlot(sink.into()));
+            return Err(GraphError::UndefinedAssetSlot(flag));
         }
-
-        let src : String = {
-            if self.freestanding_assets
-                .iter()
-                .any(|name| name.as_str() == src)
-            {
-                format!("{}::value", src)
-            }
-            else{
-                src.into()
+        if let Some(ty) = self.asset_type(&flag) {
+            if ty != TypeId::of::<bool>() {
+                return Err(GraphError::TypeMismatch(flag, sink.into()));
             }
-        };
+        }
+
+        self.conditional_bindings
+            .entry(sink.into())
+            .or_default()
+            .push((src, flag));
+        Ok(())
+    }
+
+    /// freestanding assets are provided by the `value` output of their node
+    fn qualify_source(&self, src: &str) -> String {
+        if self.freestanding_assets
+            .iter()
+            .any(|name| name.as_str() == src)
+        {
+            self.freestanding_source(src)
+        }
+        else{
+            src.into()
+        }
+    }
+
+    /// output asset of a freestanding asset node, `name::value` unless renamed
+    fn freestanding_source(&self, name: &str) -> String {
+        self.get_node(name)
+            .and_then(|node| node.get_outs().first())
+            .cloned()
+            .unwrap_or_else(|| format!("{}::value", name))
+    }
+
+    /// validates that a binding can be done, returns the actual source asset
+    fn check_binding(&self, src: &str, sink: &str) -> Result<String, GraphError> {
 
         if !self.nodes
             .values()
-            .any(|node| node.get_outs().iter().any(|name| name.as_str() == src.as_str()))
+            .any(|node| node.get_ins().iter().any(|name| name.as_str() == sink))
+        {
+            return Err(GraphError::UndefinedAssetSlot(sink.into()));
+        }
+
+        let freestanding = self.freestanding_assets.iter().any(|name| name.as_str() == src);
+        let src = self.qualify_source(src);
+
+        if !self.whatprovides.contains_key(&src)
+            && !self.bindings.contains_key(&src)
         {
-             return Err(GraphError::UndefinedAssetSlot(src.into()));
+             return Err(GraphError::UndefinedAssetSlot(src));
         }
 
-        self.bindings.insert(sink.into(), src.into());
-        Ok(())
+        // the type of a freestanding value is known at declaration
+        if freestanding || self.strict {
+            self.check_binding_types(&src, sink)?;
+        }
+
+        Ok(src)
+    }
+
+    /// Follows the chain of bindings from an input asset up to the output asset that
+    /// ultimately provides its value. None if the input is not bound.
+    pub fn resolve_source(&self, sink: &str) -> Option<String> {
+        self.resolve_source_str(sink).cloned()
+    }
+
+    fn resolve_source_str(&self, sink: &str) -> Option<&String> {
+        let mut current = self.bindings.get(sink)?;
+        // a binding chain can not be longer than the number of bindings, unless it loops
+        for _ in 0..self.bindings.len() {
+            match self.bindings.get(current) {
+                Some(next) => current = next,
+                None => return Some(current),
+            }
+        }
+        None
     }
 
     /// For a given asset name, identifies which node generates the it
-    pub fn what_provides(&self, name: &str) -> AssetProvider {
+    pub fn what_provides(&self, name: &str) -> AssetProvider<'_> {
         // which asset satisfies this input?
-        let provider = match self.get_binding_str(name) {
+        let provider = match self.resolve_source_str(name) {
             Some(asset) => asset,
             _ => name,
         };
 
         let key: String = provider.into();
-        if let Some(node) = self.whatprovides.get(&key).map(|res| res.as_ref()){
+        if let Some(node) = self.provider_of(&key){
             return AssetProvider::Node(node);
         }
 
@@ -353,7 +1131,7 @@ impl Graph {
             return AssetProvider::Preset(name);
         }
 
-        return AssetProvider::None;
+        AssetProvider::None
     }
 
     /// reports a collection of *input* assets which are not currenty bound, this elements
@@ -372,16 +1150,771 @@ impl Graph {
         &self.freestanding_assets
     }
 
-    fn iter(&self) -> std::collections::btree_map::Iter<String, Rc<NodeRunner>> {
-        self.nodes.iter()
+    /// Checks, in one pass, that every binding connects assets of the same type. Bindings
+    /// involving assets of unknown type are not checked. All mismatches are reported as
+    /// `GraphError::TypeMismatch`.
+    pub fn validate_types(&self) -> Result<(), Vec<GraphError>> {
+        let conditionals = self
+            .conditional_bindings
+            .iter()
+            .flat_map(|(sink, srcs)| srcs.iter().map(move |(src, _)| (sink, src)));
+
+        let errors: Vec<GraphError> = self
+            .bindings
+            .keys()
+            .filter_map(|sink| self.resolve_source_str(sink).map(|src| (sink, src)))
+            .chain(conditionals)
+            .filter_map(|(sink, src)| self.check_binding_types(src, sink).err())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
-}
 
-// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Checks whether a task can be executed, reporting all the problems found: cycles in
+    /// the graph (`GraphError::CycleDetected`), inputs of the task or of its dependencies
+    /// which are not bound nor have a default value (`GraphError::UndefinedAssetSlot`) and
+    /// type mismatches in the bindings (`GraphError::TypeMismatch`).
+    /// Inputs meant to be fed into the solver are reported as unbound.
+    pub fn preflight(&self, target: &str) -> Result<(), Vec<GraphError>> {
+        let node = match self.get_node(target) {
+            Some(node) => node,
+            None => return Err(vec![GraphError::NodeNotFound(target.into())]),
+        };
 
-/// type used to store results of executions and pass it to further solver instances
-pub type ValuesCache = Map<String, Rc<Any>>;
+        let mut errors = Vec::new();
+        if let Some(name) = self.find_cycle() {
+            errors.push(GraphError::CycleDetected(name));
+        }
+
+        for node in self.dependency_cone(vec![node]) {
+            for input in node.get_ins() {
+                if self.conditional_bindings.contains_key(input) {
+                    continue;
+                }
+                match self.resolve_source_str(input) {
+                    Some(src) if self.provider_of(src).is_none() => {
+                        errors.push(GraphError::UndefinedAssetSlot(src.clone()))
+                    }
+                    None if !self.defaults.contains_key(input) => {
+                        errors.push(GraphError::UndefinedAssetSlot(input.clone()))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Err(mismatches) = self.validate_types() {
+            errors.extend(mismatches);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Scans the declared assets of all nodes looking for name collisions: outputs declared
+    /// more than once (`GraphError::DuplicateOutput`), which `add_node` only detects among
+    /// different nodes, and inputs named as an output (`GraphError::RedeclaredAsset`).
+    pub fn finalize(&self) -> Result<(), Vec<GraphError>> {
+        let mut outputs: Map<&str, usize> = Map::new();
+        for out in self.nodes.values().flat_map(|node| node.get_outs()) {
+            *outputs.entry(out.as_str()).or_insert(0) += 1;
+        }
+
+        let mut errors: Vec<GraphError> = outputs
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .map(|(out, _)| GraphError::DuplicateOutput(out.to_string()))
+            .collect();
+        errors.extend(
+            self.nodes
+                .values()
+                .flat_map(|node| node.get_ins())
+                .filter(|input| outputs.contains_key(input.as_str()))
+                .map(|input| GraphError::RedeclaredAsset(input.clone())),
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Lists what `other` adds to or removes from this graph: nodes (freestanding assets
+    /// are listed apart), bindings and freestanding assets. Task implementations are not
+    /// compared.
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        use std::collections::BTreeSet;
+
+        let nodes = |graph: &Graph| -> BTreeSet<String> {
+            graph
+                .nodes
+                .keys()
+                .filter(|name| !graph.freestanding_assets.contains(name))
+                .cloned()
+                .collect()
+        };
+        let bindings = |graph: &Graph| -> BTreeSet<(String, String)> {
+            graph
+                .bindings
+                .iter()
+                .map(|(sink, src)| (src.clone(), sink.clone()))
+                .collect()
+        };
+        let freestanding =
+            |graph: &Graph| -> BTreeSet<String> { graph.freestanding_assets.iter().cloned().collect() };
+
+        let (old_nodes, new_nodes) = (nodes(self), nodes(other));
+        let (old_bindings, new_bindings) = (bindings(self), bindings(other));
+        let (old_freestanding, new_freestanding) = (freestanding(self), freestanding(other));
+        GraphDiff {
+            added_nodes: new_nodes.difference(&old_nodes).cloned().collect(),
+            removed_nodes: old_nodes.difference(&new_nodes).cloned().collect(),
+            added_bindings: new_bindings.difference(&old_bindings).cloned().collect(),
+            removed_bindings: old_bindings.difference(&new_bindings).cloned().collect(),
+            added_freestanding: new_freestanding.difference(&old_freestanding).cloned().collect(),
+            removed_freestanding: old_freestanding.difference(&new_freestanding).cloned().collect(),
+        }
+    }
+
+    /// freestanding assets which are actually bound to some input
+    pub fn active_freestanding(&self) -> Vec<&str> {
+        self.freestanding_assets
+            .iter()
+            .filter(|name| {
+                let src = self.freestanding_source(name);
+                self.bindings.values().any(|bound| *bound == src)
+            })
+            .map(|name| name.as_str())
+            .collect()
+    }
+
+    /// Enables or disables a task. Disabled tasks are never run by the solver, which reports
+    /// them as `SolverStatus::Skipped`; their dependencies are not executed either.
+    pub fn set_enabled(&mut self, node: &str, enabled: bool) -> Result<(), GraphError> {
+        if !self.nodes.contains_key(node) {
+            return Err(GraphError::NodeNotFound(node.into()));
+        }
+        self.disabled.retain(|name| name != node);
+        if !enabled {
+            self.disabled.push(node.into());
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, node: &str) -> bool {
+        !self.disabled.iter().any(|name| name == node)
+    }
+
+    /// Sets a hint of how expensive a task is. A parallel scheduler would start the most
+    /// expensive tasks first among those ready to run.
+    pub fn set_cost(&mut self, node: &str, cost: u32) -> Result<(), GraphError> {
+        if !self.nodes.contains_key(node) {
+            return Err(GraphError::NodeNotFound(node.into()));
+        }
+        self.costs.insert(node.into(), cost);
+        Ok(())
+    }
+
+    pub fn get_cost(&self, node: &str) -> Option<u32> {
+        self.costs.get(node).cloned()
+    }
+
+    /// Lets the solver run a task again, up to `attempts` more times, when it fails.
+    /// Retries run the task body again, so it must be idempotent.
+    pub fn set_retry(&mut self, node: &str, attempts: u32) -> Result<(), GraphError> {
+        if !self.nodes.contains_key(node) {
+            return Err(GraphError::NodeNotFound(node.into()));
+        }
+        self.retries.insert(node.into(), attempts);
+        Ok(())
+    }
+
+    pub fn get_retry(&self, node: &str) -> u32 {
+        self.retries.get(node).cloned().unwrap_or(0)
+    }
+
+    /// Attaches a free form annotation to a node, i.e. for editors or grouping. Metadata
+    /// is ignored by the solver but written by the DOT and JSON exports.
+    pub fn set_metadata(&mut self, node: &str, key: &str, value: String) -> Result<(), GraphError> {
+        if !self.nodes.contains_key(node) {
+            return Err(GraphError::NodeNotFound(node.into()));
+        }
+        self.metadata
+            .entry(node.into())
+            .or_default()
+            .insert(key.into(), value);
+        Ok(())
+    }
+
+    pub fn get_metadata(&self, node: &str, key: &str) -> Option<&str> {
+        self.metadata
+            .get(node)
+            .and_then(|entries| entries.get(key))
+            .map(|value| value.as_str())
+    }
+
+    /// Registers a value for an input asset, used by the solver when the input is not bound
+    /// and no value was fed for it.
+    pub fn set_default<T>(&mut self, input_asset: &str, value: T) -> Result<(), GraphError>
+    where
+        T: Clone + 'static,
+    {
+        let ty = match self
+            .nodes
+            .values()
+            .find(|node| node.get_ins().iter().any(|input| input == input_asset))
+        {
+            Some(node) => node.get_asset_type(input_asset),
+            None => return Err(GraphError::UndefinedAssetSlot(input_asset.into())),
+        };
+        if ty.is_some() && ty != Some(TypeId::of::<T>()) {
+            return Err(GraphError::TypeMismatch(std::any::type_name::<T>().into(), input_asset.into()));
+        }
+        self.defaults.insert(input_asset.into(), Rc::new(value));
+        Ok(())
+    }
+
+    /// Makes the value of an output asset readable from the solver under a different name,
+    /// the value is not duplicated. Freestanding assets can be aliased by their name.
+    pub fn alias_asset(&mut self, existing: &str, alias: &str) -> Result<(), GraphError> {
+        let existing = self.qualify_source(existing);
+        if !self.whatprovides.contains_key(&existing) {
+            return Err(GraphError::UndefinedAssetSlot(existing));
+        }
+        if self.whatprovides.contains_key(alias) {
+            return Err(GraphError::RedeclaredAsset(alias.into()));
+        }
+        self.aliases.insert(alias.into(), existing);
+        Ok(())
+    }
+
+    /// Registers an asset saved by a task although it is not among its declared outputs (i.e.
+    /// with `solver.save_value_str` from the task body), so it can be bound to inputs.
+    /// The solver has no type information for it and does not restore it from the cache.
+    pub fn declare_side_output(&mut self, node: &str, asset: &str) -> Result<(), GraphError> {
+        if !self.nodes.contains_key(node) {
+            return Err(GraphError::NodeNotFound(node.into()));
+        }
+        if self.whatprovides.contains_key(asset) {
+            return Err(GraphError::DuplicateOutput(asset.into()));
+        }
+        self.whatprovides.insert(asset.into(), node.into());
+        Ok(())
+    }
+
+    /// Binds every unbound input asset to the output asset of the same type, as long as there
+    /// is exactly one output with such type in the graph. Returns the bindings made as
+    /// (source, sink) pairs. If any input could be satisfied by several outputs, nothing is
+    /// bound and `GraphError::AmbiguousAutobind` is returned.
+    pub fn autobind_by_type(&mut self) -> Result<Vec<(String, String)>, GraphError> {
+        let mut found = Vec::new();
+
+        for node in self.nodes.values() {
+            for input in node.get_ins() {
+                if !self.what_provides(input).is_none() {
+                    continue;
+                }
+                let ty = match node.get_asset_type(input) {
+                    Some(ty) => ty,
+                    None => continue,
+                };
+
+                let candidates: Vec<&String> = self
+                    .nodes
+                    .values()
+                    .filter(|other| other.get_name() != node.get_name())
+                    .flat_map(|other| {
+                        other
+                            .get_outs()
+                            .iter()
+                            .filter(move |out| other.get_asset_type(out) == Some(ty))
+                    })
+                    .collect();
+
+                match candidates.len() {
+                    0 => {}
+                    1 => found.push((candidates[0].clone(), input.clone())),
+                    _ => return Err(GraphError::AmbiguousAutobind(input.clone())),
+                }
+            }
+        }
+
+        for (src, sink) in &found {
+            self.bindings.insert(sink.clone(), src.clone());
+        }
+        Ok(found)
+    }
+
+    /// Binds every unbound input asset to the output asset with the same base name (the part
+    /// after `::`), as long as exactly one output of another node has such name. Inputs with
+    /// several candidates are left unbound. Returns the bindings made as (source, sink) pairs.
+    pub fn bind_by_matching_names(&mut self) -> Vec<(String, String)> {
+        let base_name = |asset: &str| asset.rsplit("::").next().unwrap_or("").to_string();
+        let mut found = Vec::new();
+
+        for node in self.nodes.values() {
+            for input in node.get_ins() {
+                if !self.what_provides(input).is_none() {
+                    continue;
+                }
+                let name = base_name(input);
+
+                let candidates: Vec<&String> = self
+                    .nodes
+                    .values()
+                    .filter(|other| other.get_name() != node.get_name())
+                    .flat_map(|other| other.get_outs())
+                    .filter(|out| base_name(out) == name)
+                    .collect();
+
+                if candidates.len() == 1 {
+                    found.push((candidates[0].clone(), input.clone()));
+                }
+            }
+        }
+
+        for (src, sink) in &found {
+            self.bindings.insert(sink.clone(), src.clone());
+        }
+        found
+    }
+
+    /// Lists every asset declared in the graph, with the node owning it and its role.
+    /// Freestanding assets are reported by their output asset, the one to bind from.
+    pub fn assets(&self) -> Vec<AssetDescriptor> {
+        let mut assets = Vec::new();
+        for (name, node) in self.nodes.iter() {
+            let freestanding = self.freestanding_assets.contains(name);
+            for input in node.get_ins() {
+                assets.push(AssetDescriptor {
+                    name: input.clone(),
+                    owner: name.clone(),
+                    role: AssetRole::Input,
+                });
+            }
+            for output in node.get_outs() {
+                assets.push(AssetDescriptor {
+                    name: output.clone(),
+                    owner: name.clone(),
+                    role: if freestanding {
+                        AssetRole::Freestanding
+                    } else {
+                        AssetRole::Output
+                    },
+                });
+            }
+        }
+        assets
+    }
+
+    /// Adjacency representation of the graph: the list of node names and a matrix where
+    /// `m[i][j]` is true if some output of node `i` is bound to some input of node `j`.
+    pub fn adjacency(&self) -> (Vec<String>, Vec<Vec<bool>>) {
+        let names: Vec<String> = self.nodes.keys().cloned().collect();
+        let index = |name: &str| names.iter().position(|n| n == name);
+        let mut matrix = vec![vec![false; names.len()]; names.len()];
+
+        for sink in self.bindings.keys() {
+            let from = self
+                .resolve_source_str(sink)
+                .and_then(|src| self.provider_of(src))
+                .and_then(|n| index(n.get_name()));
+            let to = self
+                .nodes
+                .values()
+                .find(|n| n.get_ins().contains(sink))
+                .and_then(|n| index(n.get_name()));
+            if let (Some(i), Some(j)) = (from, to) {
+                matrix[i][j] = true;
+            }
+        }
+
+        (names, matrix)
+    }
+
+    /// reports nodes which are not terminals and yet none of their outputs is bound to any
+    /// input, this nodes will never be executed and are likely a wiring mistake. Producers
+    /// listed by other tasks (`from producer` in `create_node`) are consumed.
+    pub fn orphan_nodes(&self) -> Vec<String> {
+        self.nodes
+            .values()
+            .filter(|node| !node.get_outs().is_empty())
+            .filter(|node| {
+                !self.nodes.values().any(|other| {
+                    other.get_producers().iter().any(|producer| producer == node.get_name())
+                })
+            })
+            .filter(|node| {
+                !node.get_outs().iter().any(|out| {
+                    self.bindings.values().any(|src| src == out)
+                        || self
+                            .conditional_bindings
+                            .values()
+                            .flatten()
+                            .any(|(src, flag)| src == out || flag == out)
+                })
+            })
+            .map(|node| node.get_name().to_string())
+            .collect()
+    }
+
+    /// Computes every asset that can be produced starting from the given ones, following the
+    /// bindings forward. Freestanding assets can be given by name. Tasks without inputs are
+    /// not considered, unless listed. The starting assets are part of the result.
+    pub fn reachable_assets(&self, from: &[&str]) -> Vec<String> {
+        let mut available: std::collections::BTreeSet<String> =
+            from.iter().map(|asset| self.qualify_source(asset)).collect();
+        let mut fired = std::collections::BTreeSet::new();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in self.nodes.values() {
+                if fired.contains(node.get_name())
+                    || (node.get_ins().is_empty() && node.get_producers().is_empty())
+                {
+                    continue;
+                }
+                let satisfied = node.get_ins().iter().all(|input| {
+                    self.resolve_source_str(input)
+                        .is_some_and(|src| available.contains(src))
+                }) && node.get_producers().iter().all(|name| fired.contains(name.as_str()));
+                if satisfied {
+                    fired.insert(node.get_name());
+                    available.extend(node.get_outs().iter().cloned());
+                    changed = true;
+                }
+            }
+        }
+        available.into_iter().collect()
+    }
+
+    /// Length of the longest chain of bindings leading to a node, 0 for nodes which do not
+    /// depend on any other.
+    pub fn node_depth(&self, node: &str) -> Result<usize, GraphError> {
+        let target = match self.get_node(node) {
+            Some(target) => target,
+            None => return Err(GraphError::NodeNotFound(node.into())),
+        };
+        Ok(self.depths(vec![target])?[node])
+    }
+
+    /// Groups the nodes by their depth (see `node_depth`), layer `i` holds the nodes of
+    /// depth `i`. Nodes in the same layer do not depend on each other.
+    pub fn execution_layers(&self) -> Result<Vec<Vec<String>>, GraphError> {
+        let depths = self.depths(self.nodes.values().map(|node| node.as_ref()).collect())?;
+        let mut layers: Vec<Vec<String>> = Vec::new();
+        for (name, depth) in depths {
+            if layers.len() <= depth {
+                layers.resize(depth + 1, Vec::new());
+            }
+            layers[depth].push(name.into());
+        }
+        Ok(layers)
+    }
+
+    /// depth of the given nodes and all their dependencies
+    fn depths<'g>(&'g self, roots: Vec<&'g dyn NodeRunner>) -> Result<Map<&'g str, usize>, GraphError> {
+        if let Some(name) = self.find_cycle() {
+            return Err(GraphError::CycleDetected(name));
+        }
+
+        let mut depths: Map<&str, usize> = Map::new();
+        let mut stack: Vec<(&dyn NodeRunner, bool)> =
+            roots.into_iter().map(|node| (node, false)).collect();
+        while let Some((node, explored)) = stack.pop() {
+            if depths.contains_key(node.get_name()) {
+                continue;
+            }
+            let producers = self.producers_of(node);
+            if explored {
+                let depth = producers
+                    .iter()
+                    .map(|producer| depths[producer.get_name()] + 1)
+                    .max()
+                    .unwrap_or(0);
+                depths.insert(node.get_name(), depth);
+            } else {
+                stack.push((node, true));
+                stack.extend(producers.into_iter().map(|producer| (producer, false)));
+            }
+        }
+        Ok(depths)
+    }
+
+    /// Number of distinct nodes a task depends on, directly or transitively, including
+    /// itself.
+    pub fn cone_size(&self, target: &str) -> Result<usize, GraphError> {
+        match self.get_node(target) {
+            Some(node) => Ok(self.dependency_cone(vec![node]).len()),
+            None => Err(GraphError::NodeNotFound(target.into())),
+        }
+    }
+
+    /// Nodes without inputs, the entry points of the graph (freestanding assets included).
+    /// This is the counterpart of the terminals.
+    pub fn sources(&self) -> Vec<&str> {
+        self.nodes
+            .values()
+            .filter(|node| node.get_ins().is_empty() && node.get_producers().is_empty())
+            .map(|node| node.get_name())
+            .collect()
+    }
+
+    /// Structural comparison of two graphs: node names, their assets, bindings and
+    /// freestanding assets. Task bodies can not be compared and are ignored.
+    pub fn topology_eq(&self, other: &Graph) -> bool {
+        if self.nodes.len() != other.nodes.len() {
+            return false;
+        }
+        let same_nodes = self.nodes.iter().all(|(name, node)| match other.nodes.get(name) {
+            Some(o) => node.get_ins() == o.get_ins() && node.get_outs() == o.get_outs(),
+            None => false,
+        });
+
+        let mut freestanding = self.freestanding_assets.clone();
+        let mut other_freestanding = other.freestanding_assets.clone();
+        freestanding.sort();
+        other_freestanding.sort();
+
+        same_nodes
+            && self.bindings == other.bindings
+            && self.conditional_bindings == other.conditional_bindings
+            && freestanding == other_freestanding
+    }
+
+    /// Hash of the structure compared by `topology_eq`, graphs with the same topology have
+    /// the same hash. The hash (FNV-1a) does not depend on the process nor the platform, so
+    /// it can be stored, i.e. to key caches of different versions of a graph.
+    pub fn topology_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |text: &str| {
+            // every string is terminated, so consecutive strings can not be confused
+            for byte in text.bytes().chain(std::iter::once(0)) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+
+        for (name, node) in &self.nodes {
+            feed("node");
+            feed(name);
+            node.get_ins().iter().for_each(|input| feed(input));
+            feed("->");
+            node.get_outs().iter().for_each(|output| feed(output));
+        }
+        for (sink, src) in &self.bindings {
+            feed("binding");
+            feed(src);
+            feed(sink);
+        }
+        for (sink, entries) in &self.conditional_bindings {
+            for (src, flag) in entries {
+                feed("conditional");
+                feed(src);
+                feed(sink);
+                feed(flag);
+            }
+        }
+        let mut freestanding: Vec<&String> = self.freestanding_assets.iter().collect();
+        freestanding.sort();
+        for name in freestanding {
+            feed("freestanding");
+            feed(name);
+        }
+        hash
+    }
+
+    /// nodes with their names, according to the ordering of the graph
+    fn iter(&self) -> std::vec::IntoIter<(&String, &NodeStorage)> {
+        let nodes: Vec<(&String, &NodeStorage)> = match self.ordering {
+            NodeOrdering::Lexicographic => self.nodes.iter().collect(),
+            NodeOrdering::Insertion => self
+                .insertion_order
+                .iter()
+                .filter_map(|name| self.nodes.get_key_value(name))
+                .collect(),
+        };
+        nodes.into_iter()
+    }
+
+    /// type of an asset declared by any node, if known
+    fn asset_type(&self, asset: &str) -> Option<TypeId> {
+        self.nodes.values().find_map(|node| node.get_asset_type(asset))
+    }
+
+    /// checks that source and sink assets have the same type, when both types are known
+    fn check_binding_types(&self, src: &str, sink: &str) -> Result<(), GraphError> {
+        match (self.asset_type(src), self.asset_type(sink)) {
+            (Some(a), Some(b)) if a != b => Err(GraphError::TypeMismatch(src.into(), sink.into())),
+            _ => Ok(()),
+        }
+    }
+
+    /// nodes producing the bound inputs of the given node
+    fn producers_of(&self, node: &dyn NodeRunner) -> Vec<&dyn NodeRunner> {
+        node.get_ins()
+            .iter()
+            .filter_map(|input| self.resolve_source_str(input))
+            .filter_map(|src| self.provider_of(src))
+            .chain(node.get_producers().iter().filter_map(|name| self.get_node(name)))
+            .collect()
+    }
+
+    /// the given nodes and all the nodes they depend on, conditional sources included
+    fn dependency_cone<'g>(&'g self, roots: Vec<&'g dyn NodeRunner>) -> Vec<&'g dyn NodeRunner> {
+        let mut visited = std::collections::BTreeSet::new();
+        let mut cone = Vec::new();
+        let mut pending = roots;
+        while let Some(node) = pending.pop() {
+            if !visited.insert(node.get_name()) {
+                continue;
+            }
+            pending.extend(self.producers_of(node));
+            for input in node.get_ins() {
+                for (src, flag) in self.conditional_bindings.get(input).into_iter().flatten() {
+                    pending.extend(self.provider_of(src));
+                    pending.extend(self.provider_of(flag));
+                }
+            }
+            cone.push(node);
+        }
+        cone
+    }
+
+    /// looks for a dependency cycle, returns the name of one node taking part in it
+    fn find_cycle(&self) -> Option<String> {
+        // 1: in the current path, 2: fully explored
+        let mut state: Map<&str, u8> = Map::new();
+
+        for start in self.nodes.values() {
+            if state.contains_key(start.get_name()) {
+                continue;
+            }
+            let mut stack: Vec<(&dyn NodeRunner, bool)> = vec![(start.as_ref(), false)];
+            while let Some((node, explored)) = stack.pop() {
+                let name = node.get_name();
+                if explored {
+                    state.insert(name, 2);
+                    continue;
+                }
+                match state.get(name) {
+                    Some(2) => continue,
+                    Some(_) => return Some(name.into()),
+                    None => {}
+                }
+                state.insert(name, 1);
+                stack.push((node, true));
+                for producer in self.producers_of(node) {
+                    match state.get(producer.get_name()) {
+                        Some(1) => return Some(producer.get_name().into()),
+                        Some(_) => {}
+                        None => stack.push((producer, false)),
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Helper to construct a graph in any order: bindings can be declared before the nodes
+/// they connect. All bindings are resolved, and the whole graph validated, on `build`.
+#[derive(Default)]
+pub struct GraphBuilder {
+    graph: Graph,
+    bindings: Vec<(String, String)>,
+    errors: Vec<GraphError>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> GraphBuilder {
+        GraphBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn add_node<F>(&mut self, node: Node<F>) -> &mut GraphBuilder
+    where
+        F: 'static + Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>,
+    {
+        if let Err(e) = self.graph.add_node(node) {
+            self.errors.push(e);
+        }
+        self
+    }
+
+    pub fn define_freestanding_asset<T: 'static + Clone>(&mut self, name: &str, val: T) -> &mut GraphBuilder {
+        if let Err(e) = self.graph.define_freestanding_asset(name, val) {
+            self.errors.push(e);
+        }
+        self
+    }
+
+    /// Declares a binding, neither the source nor the sink need to exist yet.
+    pub fn bind_asset(&mut self, src: &str, sink: &str) -> &mut GraphBuilder {
+        self.bindings.push((src.into(), sink.into()));
+        self
+    }
+
+    /// Resolves all bindings and validates the resulting graph: redefined nodes, undefined
+    /// assets, type mismatches and cycles. All problems found are reported. Bindings may be
+    /// declared in any order, chains (an input bound to another input) are type checked
+    /// once all bindings are in place.
+    pub fn build(self) -> Result<Graph, Vec<GraphError>> {
+        let mut graph = self.graph;
+        let mut errors = self.errors;
+
+        // an input can only be the source of a binding once it is bound itself, pending
+        // bindings are retried until no more of them can be done
+        let mut pending: Vec<&(String, String)> = self.bindings.iter().collect();
+        loop {
+            let before = pending.len();
+            pending.retain(|(src, sink)| graph.bind_asset(src, sink).is_err());
+            if pending.len() == before {
+                break;
+            }
+        }
+        for (src, sink) in pending {
+            if let Err(e) = graph.bind_asset(src, sink) {
+                errors.push(e);
+            }
+        }
+
+        let sinks: std::collections::BTreeSet<&String> = self.bindings.iter().map(|(_, sink)| sink).collect();
+        for sink in sinks {
+            if let Some(resolved) = graph.resolve_source(sink) {
+                if let Err(e) = graph.check_binding_types(&resolved, sink) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        if let Some(node) = graph.find_cycle() {
+            errors.push(GraphError::CycleDetected(node));
+        }
+
+        if errors.is_empty() {
+            Ok(graph)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// type used to store results of executions and pass it to further solver instances
+pub type ValuesCache = Map<String, Rc<dyn Any>>;
 
 /// A convenience trait to allow the storage of asset values in between tasks or 
 /// graph executions.
@@ -395,6 +1928,7 @@ pub trait Cache {
     /// Saves a value to be available during execution. This routine
     /// can be used to feed initial values for Assets. i.e. unbond assets Assets not
     /// generated by any Task.
+    #[allow(clippy::ptr_arg)]
     fn save_value<T>(&mut self, name: &String, value: T)
     where
         T: Clone + 'static;
@@ -405,6 +1939,31 @@ pub trait Cache {
     fn save_value_str<T>(&mut self, name: &str, value: T)
     where
         T: Clone + 'static;
+
+    /// Retrieves the shared pointer holding a value, without cloning the value.
+    fn get_raw(&self, name: &str) -> Option<Rc<dyn Any>>;
+
+    /// Saves a shared pointer as value of an asset, the same pointer can be stored under
+    /// several names without duplicating the value.
+    fn save_raw(&mut self, name: &str, value: Rc<dyn Any>);
+
+    /// Adds all the values of another cache, values are shared not cloned. The policy
+    /// decides which value is kept for assets present in both. The default goes through
+    /// `get_raw` and `save_raw`.
+    fn merge_cache(&mut self, other: ValuesCache, policy: MergePolicy) {
+        for (name, value) in other {
+            if policy == MergePolicy::Overwrite || self.get_raw(&name).is_none() {
+                self.save_raw(&name, value);
+            }
+        }
+    }
+}
+
+/// How to solve conflicts when merging caches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergePolicy {
+    KeepExisting,
+    Overwrite,
 }
 
 impl Cache for ValuesCache {
@@ -433,22 +1992,138 @@ impl Cache for ValuesCache {
     where
         T: Clone + 'static,
     {
-        let ptr: Rc<Any> = Rc::new(value);
+        let ptr: Rc<dyn Any> = Rc::new(value);
         self.insert(name.into(), ptr);
     }
-}
 
-// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    fn get_raw(&self, name: &str) -> Option<Rc<dyn Any>> {
+        self.get(name).cloned()
+    }
 
-/// this trait allows us to overload behavior for custom types
-/// in this manner comparison can be optimized or bypassed for
-/// custom types
-pub trait Comparable {
-    fn ne(&self, other: &Self) -> bool;
+    fn save_raw(&mut self, name: &str, value: Rc<dyn Any>) {
+        self.insert(name.into(), value);
+    }
 }
 
-impl<T> Comparable for T
+/// Cache holding up to `capacity` values, saving a new value beyond the capacity evicts
+/// the least recently used one (read or saved). Evicted assets are gone, reading them
+/// reports `SolverError::AssetNotCreated`. It is meant to keep values apart from the
+/// solver, which always stores its values in a `ValuesCache`.
+pub struct BoundedCache {
+    values: ValuesCache,
+    capacity: usize,
+    last_use: std::cell::RefCell<Map<String, u64>>,
+    clock: std::cell::Cell<u64>,
+}
+
+impl BoundedCache {
+    pub fn new(capacity: usize) -> BoundedCache {
+        BoundedCache {
+            values: ValuesCache::new(),
+            capacity,
+            last_use: std::cell::RefCell::new(Map::new()),
+            clock: std::cell::Cell::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// checks for an asset, it does not count as a use
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    fn touch(&self, name: &str) {
+        let tick = self.clock.get() + 1;
+        self.clock.set(tick);
+        self.last_use.borrow_mut().insert(name.into(), tick);
+    }
+
+    fn evict(&mut self) {
+        while self.values.len() > self.capacity {
+            let oldest = self
+                .last_use
+                .borrow()
+                .iter()
+                .min_by_key(|(_, tick)| **tick)
+                .map(|(name, _)| name.clone());
+            match oldest {
+                Some(name) => {
+                    self.values.remove(&name);
+                    self.last_use.borrow_mut().remove(&name);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl Cache for BoundedCache {
+    fn get_value<T>(&self, name: &str) -> Result<T, SolverError>
+    where
+        T: Clone + 'static,
+    {
+        let value = self.values.get_value(name)?;
+        self.touch(name);
+        Ok(value)
+    }
+
+    fn save_value<T>(&mut self, name: &String, value: T)
+    where
+        T: Clone + 'static,
+    {
+        self.save_value_str(name.as_str(), value);
+    }
+
+    fn save_value_str<T>(&mut self, name: &str, value: T)
+    where
+        T: Clone + 'static,
+    {
+        self.save_raw(name, Rc::new(value));
+    }
+
+    fn get_raw(&self, name: &str) -> Option<Rc<dyn Any>> {
+        let value = self.values.get_raw(name)?;
+        self.touch(name);
+        Some(value)
+    }
+
+    fn save_raw(&mut self, name: &str, value: Rc<dyn Any>) {
+        self.values.save_raw(name, value);
+        self.touch(name);
+        self.evict();
+    }
+
+    fn merge_cache(&mut self, other: ValuesCache, policy: MergePolicy) {
+        for (name, value) in other {
+            if policy == MergePolicy::Overwrite || !self.values.contains_key(&name) {
+                self.save_raw(&name, value);
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// this trait allows us to overload behavior for custom types
+/// in this manner comparison can be optimized or bypassed for
+/// custom types
+pub trait Comparable {
+    fn ne(&self, other: &Self) -> bool;
+}
+
+impl<T> Comparable for T
 where
     T: cmp::PartialEq,
 {
@@ -466,6 +2141,19 @@ pub struct GraphSolver<'a, 'b> {
     graph: &'a Graph,
     cache: ValuesCache,
     last_cache: &'b mut ValuesCache,
+    base: Option<&'b ValuesCache>,
+    max_depth: Option<usize>,
+    run_counts: Map<String, u32>,
+    current_node: Option<String>,
+    caching: bool,
+    check_outputs: bool,
+    missing_outputs: Vec<String>,
+    warnings: Vec<String>,
+    overrides: Vec<String>,
+    check_overwrites: bool,
+    skip_unsatisfiable: bool,
+    #[cfg(feature = "metrics")]
+    clone_count: std::cell::Cell<usize>,
 }
 
 /// Errors that may happen during a Solver instance execution
@@ -487,16 +2175,50 @@ pub enum SolverError {
     NodeNotFound(String),
     /// The current graph has no terminal nodes (no output)
     NoTerminalsDefined,
+    /// The dependency chain of the executed task is deeper than the configured limit
+    DepthExceeded(usize),
+    /// The time budget for the solve was exhausted
+    Timeout,
+    /// The solve was cancelled before completion
+    Cancelled,
+    /// A task failed during its execution
+    InNode {
+        node: String,
+        source: Box<SolverError>,
+    },
+    /// Error raised by the body of a task
+    Task(Box<dyn std::error::Error + Send + Sync>),
 
     /// WIP
     NotImplemented
 }
 
+impl SolverError {
+    /// wraps an error raised by the body of a task, i.e. `text.parse().map_err(SolverError::task)?`
+    pub fn task<E>(error: E) -> SolverError
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        SolverError::Task(Box::new(error))
+    }
+}
+
 /// Type to differentiate cached tasks from executed ones
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SolverStatus {
     Cached,
     Executed,
+    /// The task was intentionally not run, i.e. it is disabled
+    Skipped,
+}
+
+/// Summary of a solve where some of the requested tasks may not have been executed
+#[derive(Debug, Default)]
+pub struct ExecutionReport {
+    /// tasks processed, with the resulting status (disabled tasks are `Skipped`)
+    pub executed: Vec<(String, SolverStatus)>,
+    /// tasks which could not be executed because their prerequisites are not satisfied
+    pub skipped: Vec<String>,
 }
 
 impl<'a, 'b> GraphSolver<'a, 'b> {
@@ -504,63 +2226,479 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
     /// the cache may be empty.
     pub fn new(graph: &'a Graph, last_cache: &'b mut ValuesCache) -> GraphSolver<'a, 'b> {
         GraphSolver {
-            graph: graph,
+            graph,
             cache: ValuesCache::new(),
-            last_cache: last_cache,
+            last_cache,
+            base: None,
+            max_depth: None,
+            run_counts: Map::new(),
+            current_node: None,
+            caching: true,
+            check_outputs: cfg!(debug_assertions),
+            missing_outputs: Vec::new(),
+            warnings: Vec::new(),
+            overrides: Vec::new(),
+            check_overwrites: false,
+            skip_unsatisfiable: false,
+            #[cfg(feature = "metrics")]
+            clone_count: std::cell::Cell::new(0),
         }
     }
 
+    /// creates a solver reading from a shared, read-only `base` cache as well. Values found
+    /// in `base` are available to the tasks as if computed during this solve, so their
+    /// producers do not run. Computed values are only written to `scratch`, which is
+    /// also used as the cache of the previous solve.
+    pub fn with_overlay(
+        graph: &'a Graph,
+        base: &'b ValuesCache,
+        scratch: &'b mut ValuesCache,
+    ) -> GraphSolver<'a, 'b> {
+        let mut solver = GraphSolver::new(graph, scratch);
+        solver.base = Some(base);
+        solver
+    }
+
+    /// Points the solver to a different graph, values computed so far are kept and can be
+    /// used by the tasks of the new graph. Tasks producing assets already in the cache
+    /// will not run again.
+    pub fn rebind_graph(&mut self, graph: &'a Graph) {
+        self.graph = graph;
+    }
+
+    /// limits how deep the dependency chain of an executed task can be, tasks needing a
+    /// longer chain of producers will fail with `SolverError::DepthExceeded`.
+    /// By default depth is unbounded.
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.max_depth = Some(depth);
+    }
+
+    /// enables or disables the reuse of values from the previous solve, when disabled every
+    /// task runs regardless of its inputs being unchanged. Enabled by default.
+    pub fn set_caching(&mut self, enabled: bool) {
+        self.caching = enabled;
+    }
+
+    pub fn caching_enabled(&self) -> bool {
+        self.caching
+    }
+
+    /// enables the check of outputs after each task runs, declared outputs which were not
+    /// saved by the task are reported by `missing_outputs`. Enabled in debug builds.
+    pub fn set_check_outputs(&mut self, enabled: bool) {
+        self.check_outputs = enabled;
+    }
+
+    /// output assets declared by tasks that ran successfully but did not produce them,
+    /// usually a hand written task forgetting to save a value.
+    pub fn missing_outputs(&self) -> &[String] {
+        &self.missing_outputs
+    }
+
+    /// Reports a non fatal issue, tasks can use it from their body. Warnings are collected
+    /// during the solve and can be read with `warnings`.
+    pub fn warn(&mut self, msg: String) {
+        self.warnings.push(msg);
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// enables a check on every saved value, overwriting an asset with a value of a
+    /// different type is reported in `warnings`. Disabled by default.
+    pub fn set_check_overwrites(&mut self, enabled: bool) {
+        self.check_overwrites = enabled;
+    }
+
+    /// makes `execute_terminals` skip the terminals which can not be satisfied, as
+    /// `execute_terminals_partial` does. Disabled by default, all terminals must run.
+    pub fn set_skip_unsatisfiable(&mut self, skip: bool) {
+        self.skip_unsatisfiable = skip;
+    }
+
+    /// number of values cloned out of the solver by `get_value`, which includes every input
+    /// read by the tasks.
+    #[cfg(feature = "metrics")]
+    pub fn clone_count(&self) -> usize {
+        self.clone_count.get()
+    }
+
     pub fn get_binding(&self, name: &str) -> Result<&String, SolverError> {
-        match self.graph.get_binding_str(name) {
+        match self.binding_of(name) {
             Some(x) => Ok(x),
             None => Err(SolverError::AssetUnbound(name.into())),
         }
     }
 
+    /// Name of the asset the solver reads to satisfy a task input, following the bindings
+    /// (freestanding assets and conditional bindings included). Fails with
+    /// `SolverError::AssetNotDeclared` if no task has such input and with
+    /// `SolverError::AssetUnbound` if it is not bound.
+    pub fn resolve_input(&self, task_input: &str) -> Result<String, SolverError> {
+        if !self.graph
+            .nodes
+            .values()
+            .any(|node| node.get_ins().iter().any(|input| input == task_input))
+        {
+            return Err(SolverError::AssetNotDeclared(task_input.into()));
+        }
+        self.get_binding(task_input).cloned()
+    }
+
+    /// output asset satisfying an input, honoring conditional bindings whose flag is set
+    fn binding_of(&self, name: &str) -> Option<&'a String> {
+        let graph: &'a Graph = self.graph;
+        if let Some(conditionals) = graph.conditional_bindings.get(name) {
+            for (src, flag) in conditionals {
+                if let Ok(true) = self.get_value::<bool>(flag) {
+                    return Some(graph.resolve_source_str(src).unwrap_or(src));
+                }
+            }
+        }
+        graph.resolve_source_str(name)
+    }
+
+    /// computes the flags of the conditional bindings of an input, up to the first one set,
+    /// so the input can be resolved. Flag producers are run as any other task, `check` is
+    /// called before each of them.
+    fn evaluate_flags(
+        &mut self,
+        input: &str,
+        check: &mut dyn FnMut() -> Result<(), SolverError>,
+    ) -> Result<(), SolverError> {
+        let graph: &'a Graph = self.graph;
+        if let Some(conditionals) = graph.conditional_bindings.get(input) {
+            for (_, flag) in conditionals {
+                if self.lookup(flag).is_none() {
+                    match graph.what_provides(flag) {
+                        AssetProvider::Node(n) => {
+                            let to_run = self.plan_checked(&[n], check)?;
+                            self.run_all_checked(&to_run, check)?;
+                        }
+                        _ => return Err(SolverError::AssetNotProduced(flag.clone())),
+                    }
+                }
+                if let Ok(true) = self.get_value::<bool>(flag) {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of the asset read to satisfy an input: the source of its binding or, when the
+    /// input is not bound, the input itself (which then must be fed into the solver).
+    pub fn input_source<'s>(&'s self, name: &'s str) -> &'s str {
+        let name = self.graph.current_name(name);
+        match self.get_binding(name) {
+            Ok(asset) => asset.as_str(),
+            _ => name,
+        }
+    }
+
+    /// Reports, for each input of a task, whether its value can be resolved right now: it
+    /// was fed or defaulted, or it is bound to an asset which is already in the cache (of
+    /// this solve or the previous one) or can be produced. Empty if the task does not exist.
+    pub fn input_status(&self, node: &str) -> Vec<(String, bool)> {
+        let mut visiting = Vec::new();
+        match self.graph.get_node(node) {
+            Some(node) => node
+                .get_ins()
+                .iter()
+                .map(|input| (input.clone(), self.can_resolve(input, &mut visiting)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn can_resolve<'g>(&'g self, input: &str, visiting: &mut Vec<&'g str>) -> bool {
+        if self.lookup(input).is_some() {
+            return true;
+        }
+        let src = match self.binding_of(input) {
+            Some(src) => src,
+            None => return self.graph.defaults.contains_key(input),
+        };
+        if self.lookup(src).is_some() || self.last_cache.contains_key(src) {
+            return true;
+        }
+        match self.graph.what_provides(src) {
+            AssetProvider::Node(producer) => {
+                // a loop can not be resolved
+                if visiting.contains(&producer.get_name()) {
+                    return false;
+                }
+                visiting.push(producer.get_name());
+                let resolvable = producer
+                    .get_producers()
+                    .iter()
+                    .all(|name| self.graph.get_node(name).is_some())
+                    && producer
+                        .get_ins()
+                        .iter()
+                        .all(|input| self.can_resolve(input, visiting));
+                visiting.pop();
+                resolvable
+            }
+            AssetProvider::Preset(_) => true,
+            AssetProvider::None => false,
+        }
+    }
+
+    /// Saves a value to satisfy a task input. The value is stored under the asset the input
+    /// reads from, see `input_source`.
+    pub fn feed_input<T>(&mut self, sink: &str, value: T)
+    where
+        T: Clone + 'static,
+    {
+        let name = self.input_source(sink).to_string();
+        self.save_value_str(&name, value);
+    }
+
+    /// Replaces the value of a freestanding asset for this solve only, its node will not run.
+    /// The overriding value is not kept for the next solve, which will use the value
+    /// declared in the graph again.
+    pub fn override_freestanding<T>(&mut self, name: &str, value: T) -> Result<(), SolverError>
+    where
+        T: Clone + 'static,
+    {
+        if !self.graph.get_freestanding_assets().iter().any(|asset| asset == name) {
+            return Err(SolverError::AssetNotDeclared(name.into()));
+        }
+        let asset = self.graph.freestanding_source(name);
+        if self.graph.asset_type(&asset) != Some(TypeId::of::<T>()) {
+            return Err(SolverError::AssetWrongType(asset));
+        }
+        self.save_value_str(&asset, value);
+        self.overrides.push(asset);
+        Ok(())
+    }
+
     /// Executes a task by name, all tasks needed to provide Assets
-    /// are transitively executed
+    /// are transitively executed.
+    /// If a task fails, the error names it (`SolverError::InNode`) and the values produced
+    /// by the tasks which already ran remain available through `get_values`.
     pub fn execute(&mut self, name: &str) -> Result<SolverStatus, SolverError> {
         let node = self.graph.get_node(name);
         if node.is_none() {
             return Err(SolverError::NodeNotFound(name.into()));
         }
+
+        // nothing changed since the last solve, reuse all values without visiting the tasks
+        if let Some(cone) = self.cached_cone(node.unwrap()) {
+            for out in cone.iter().flat_map(|node| node.get_outs()) {
+                if let Some(value) = self.last_cache.get(out) {
+                    self.cache.insert(out.clone(), Rc::clone(value));
+                }
+            }
+            return Ok(SolverStatus::Cached);
+        }
+
         self.execute_all(&[node.unwrap()])
     }
 
+    /// Collects the task and all the tasks it depends on, only if all of them would be
+    /// served from the cache of the previous solve: nothing was computed or fed yet in
+    /// this solve and every input and output involved is in the last cache.
+    /// Tasks which can not be decided beforehand (not cache aware, disabled, conditionally
+    /// bound, without outputs, consuming all outputs of producers or reading feedback)
+    /// prevent the fast path, as does the base cache of an overlay, which may provide
+    /// values different from the previous solve.
+    fn cached_cone(&self, target: &'a dyn NodeRunner) -> Option<Vec<&'a dyn NodeRunner>> {
+        if !self.caching || !self.cache.is_empty() || self.base.is_some() {
+            return None;
+        }
+
+        let graph = self.graph;
+        let cached = |node: &&'a dyn NodeRunner| {
+            node.is_cache_aware()
+                && graph.is_enabled(node.get_name())
+                && node.get_producers().is_empty()
+                && node.get_feedback().is_empty()
+                && node.get_ins().iter().all(|input| {
+                    !graph.conditional_bindings.contains_key(input)
+                        && graph
+                            .resolve_source_str(input)
+                            .is_some_and(|src| self.last_cache.contains_key(src))
+                })
+                && !node.get_outs().is_empty()
+                && node.get_outs().iter().all(|out| self.last_cache.contains_key(out))
+        };
+
+        let cone = graph.dependency_cone(vec![target]);
+        if cone.iter().all(cached) {
+            Some(cone)
+        } else {
+            None
+        }
+    }
+
+    /// Serves a task and all the tasks it depends on from the cache of the previous solve,
+    /// no task is ever run. Conditional bindings are resolved as `execute` does, with the
+    /// flags read from the cache. Fails with `SolverError::AssetNotCreated` if any of the
+    /// outputs involved is not cached, in such case nothing is loaded.
+    pub fn execute_from_cache_only(&mut self, target: &str) -> Result<SolverStatus, SolverError> {
+        let graph: &'a Graph = self.graph;
+        let node = match graph.get_node(target) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(target.into())),
+        };
+
+        let mut values = ValuesCache::new();
+        let mut queue = vec![node];
+        let mut seen = std::collections::BTreeSet::new();
+        while let Some(node) = queue.pop() {
+            if !seen.insert(node.get_name()) {
+                continue;
+            }
+            for out in node.get_outs() {
+                match self.last_cache.get(out) {
+                    Some(value) => values.insert(out.clone(), Rc::clone(value)),
+                    None => return Err(SolverError::AssetNotCreated(out.clone())),
+                };
+            }
+
+            for input in node.get_ins() {
+                let mut source = None;
+                for (src, flag) in graph.conditional_bindings.get(input).into_iter().flatten() {
+                    let value = match self.lookup(flag).or_else(|| self.last_cache.get(flag)) {
+                        Some(value) => value,
+                        None => return Err(SolverError::AssetNotCreated(flag.clone())),
+                    };
+                    if let Some(n) = graph.provider_of(flag) {
+                        queue.push(n);
+                    }
+                    if let Some(true) = value.downcast_ref::<bool>() {
+                        source = Some(graph.resolve_source_str(src).unwrap_or(src));
+                        break;
+                    }
+                }
+                let source = match source.or_else(|| graph.resolve_source_str(input)) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                match graph.what_provides(source) {
+                    AssetProvider::Node(n) => queue.push(n),
+                    AssetProvider::Preset(name) => queue.extend(graph.get_node(name)),
+                    AssetProvider::None => return Err(SolverError::AssetNotProduced(source.clone())),
+                }
+            }
+
+            for producer in node.get_producers() {
+                match graph.get_node(producer) {
+                    Some(n) => queue.push(n),
+                    None => return Err(SolverError::NodeNotFound(producer.clone())),
+                }
+            }
+        }
+        self.cache.extend(values);
+        Ok(SolverStatus::Cached)
+    }
+
+    /// Executes every terminal task. Fails if any of them can not be satisfied, unless
+    /// `set_skip_unsatisfiable` is enabled; then the status is the one of the last
+    /// terminal executed.
     pub fn execute_terminals(&mut self) -> Result<SolverStatus, SolverError> {
-        let tmp: Vec<&NodeRunner> = self.graph
-            .get_terminals()
-            .iter()
-            .map(|x| x.as_ref())
-            .collect();
+        if self.skip_unsatisfiable {
+            let report = self.execute_terminals_partial()?;
+            return Ok(report.executed.last().expect("at least one executed").1);
+        }
+        let tmp: Vec<&dyn NodeRunner> = self.graph.get_terminals();
         if tmp.is_empty() {
             return Err(SolverError::NoTerminalsDefined);
         }
         self.execute_all(tmp.as_slice())
     }
 
-    fn execute_all(&mut self, nodes: &[&NodeRunner]) -> Result<SolverStatus, SolverError> {
+    /// Executes every terminal task whose prerequisites can be satisfied, terminals which
+    /// can not be satisfied are skipped and listed in the report.
+    /// Only fails if no terminal at all could be executed.
+    pub fn execute_terminals_partial(&mut self) -> Result<ExecutionReport, SolverError> {
+        let graph = self.graph;
+        let mut report = ExecutionReport::default();
+        let mut first_error = None;
+
+        for terminal in graph.get_terminals() {
+            let name = terminal.get_name().to_string();
+            match self.plan(&[terminal]) {
+                Ok(to_run) => {
+                    let status = self.run_all(&to_run)?;
+                    report.executed.push((name, status));
+                }
+                Err(e) => {
+                    report.skipped.push(name);
+                    if first_error.is_none() {
+                        first_error = Some(e);
+                    }
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if report.executed.is_empty() => Err(e),
+            None if report.executed.is_empty() => Err(SolverError::NoTerminalsDefined),
+            _ => Ok(report),
+        }
+    }
+
+    fn execute_all(&mut self, nodes: &[&'a dyn NodeRunner]) -> Result<SolverStatus, SolverError> {
+        let to_run = self.plan(nodes)?;
+        self.run_all(&to_run)
+    }
+
+    /// computes the list of tasks, in execution order, needed to satisfy the given ones.
+    fn plan(&mut self, nodes: &[&'a dyn NodeRunner]) -> Result<Vec<&'a dyn NodeRunner>, SolverError> {
+        self.plan_checked(nodes, &mut || Ok(()))
+    }
+
+    /// as `plan`, the flags of conditional bindings are computed while planning and `check`
+    /// is called before running each of their producers.
+    fn plan_checked(
+        &mut self,
+        nodes: &[&'a dyn NodeRunner],
+        check: &mut dyn FnMut() -> Result<(), SolverError>,
+    ) -> Result<Vec<&'a dyn NodeRunner>, SolverError> {
         let mut queue = Vec::new();
         let mut to_run = Vec::new();
 
         for n in nodes {
-            queue.push(*n);
+            queue.push((*n, 0));
         }
 
-        while !queue.is_empty() {
-            let node = queue.pop().unwrap();
+        while let Some((node, depth)) = queue.pop() {
+            if let Some(max) = self.max_depth {
+                if depth > max {
+                    return Err(SolverError::DepthExceeded(max));
+                }
+            }
+
+            // disabled tasks will not run, no need to satisfy their inputs
+            if !self.graph.is_enabled(node.get_name()) {
+                to_run.push(node);
+                continue;
+            }
 
             for input in node.get_ins() {
-                match self.graph.get_binding(input) {
+                self.evaluate_flags(input, check)?;
+                match self.binding_of(input) {
                     None => {
-                        if !self.cache.contains_key(input) {
-                            return Err(SolverError::AssetNotDeclared(input.clone()));
+                        if self.lookup(input).is_none() {
+                            match self.graph.defaults.get(input) {
+                                Some(value) => {
+                                    self.cache.insert(input.clone(), Rc::clone(value));
+                                }
+                                None => return Err(SolverError::AssetNotDeclared(input.clone())),
+                            }
                         }
                     }
                     Some(input_binding) => {
                         match self.graph.what_provides(input_binding) {
-                            AssetProvider::Node(n) => queue.push(n),
-                            AssetProvider::Preset(_) => return Err(SolverError::NotImplemented), 
+                            AssetProvider::Node(n) => queue.push((n, depth + 1)),
+                            AssetProvider::Preset(name) => match self.graph.get_node(name) {
+                                Some(n) => queue.push((n, depth + 1)),
+                                None => return Err(SolverError::AssetNotProduced(name.clone())),
+                            },
                             AssetProvider::None => {
                                 return Err(SolverError::AssetNotProduced(input_binding.clone()));
                             }
@@ -569,14 +2707,209 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
                 }
             }
 
+            for producer in node.get_producers() {
+                match self.graph.get_node(producer) {
+                    Some(n) => queue.push((n, depth + 1)),
+                    None => return Err(SolverError::NodeNotFound(producer.clone())),
+                }
+            }
+
             to_run.push(node);
         }
 
-        for node in to_run.iter().rev() {
-            let _r = node.run(self)?;
+        // a task reached through several paths is listed several times, its first
+        // appearance in execution order already precedes all its dependents
+        to_run.reverse();
+        let mut seen = std::collections::BTreeSet::new();
+        to_run.retain(|node| seen.insert(node.get_name()));
+        Ok(to_run)
+    }
+
+    /// Executes a task and retrieves one of its outputs, `output` is the name of the asset
+    /// without the task prefix.
+    pub fn run<T>(&mut self, node: &str, output: &str) -> Result<T, SolverError>
+    where
+        T: Clone + 'static,
+    {
+        let asset = format!("{}::{}", node, output);
+        match self.graph.get_node(node) {
+            None => return Err(SolverError::NodeNotFound(node.into())),
+            Some(n) if !n.get_outs().contains(&asset) => {
+                return Err(SolverError::AssetNotProduced(asset))
+            }
+            _ => {}
+        }
+        self.execute(node)?;
+        self.get_value::<T>(&asset)
+    }
+
+    /// Executes a task, like `run`, and transforms the requested output with `f`. The
+    /// transformed value is returned, the cache keeps the output as produced by the task.
+    pub fn execute_map<T, U, F>(&mut self, node: &str, output: &str, f: F) -> Result<U, SolverError>
+    where
+        T: Clone + 'static,
+        F: FnOnce(T) -> U,
+    {
+        self.run::<T>(node, output).map(f)
+    }
+
+    /// Executes a task by name like `execute`, but no further tasks are scheduled once the
+    /// time budget is exhausted and `SolverError::Timeout` is returned. A running task can not
+    /// be interrupted, so the solve may take longer than the budget.
+    pub fn execute_with_timeout(&mut self, name: &str, budget: Duration) -> Result<SolverStatus, SolverError> {
+        let node = match self.graph.get_node(name) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(name.into())),
+        };
+        let start = Instant::now();
+        let mut check = || {
+            if start.elapsed() > budget {
+                return Err(SolverError::Timeout);
+            }
+            Ok(())
+        };
+        let to_run = self.plan_checked(&[node], &mut check)?;
+        self.run_all_checked(&to_run, &mut check)
+    }
+
+    /// Executes a task by name like `execute`, the flag is checked before each task is run
+    /// and, once set, no further tasks are run and `SolverError::Cancelled` is returned.
+    /// A running task can not be interrupted.
+    pub fn execute_cancellable(&mut self, target: &str, cancel: &AtomicBool) -> Result<SolverStatus, SolverError> {
+        let node = match self.graph.get_node(target) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(target.into())),
+        };
+        let mut check = || {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(SolverError::Cancelled);
+            }
+            Ok(())
+        };
+        let to_run = self.plan_checked(&[node], &mut check)?;
+        self.run_all_checked(&to_run, &mut check)
+    }
+
+    /// Executes all the tasks a task depends on, but not the task itself, so its inputs
+    /// can be inspected before it runs. The status is the one of the last task run,
+    /// `SolverStatus::Cached` if the task has no prerequisites.
+    pub fn execute_prerequisites(&mut self, target: &str) -> Result<SolverStatus, SolverError> {
+        let node = match self.graph.get_node(target) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(target.into())),
+        };
+        let mut to_run = self.plan(&[node])?;
+        to_run.retain(|node| node.get_name() != target);
+        if to_run.is_empty() {
+            return Ok(SolverStatus::Cached);
+        }
+        self.run_all(&to_run)
+    }
+
+    /// Executes a task by name like `execute`, calling `progress` after each task of its
+    /// dependency cone is processed (run, cached or skipped) with the fraction done so
+    /// far, from 0.0 to 1.0. Conditional sources which are not selected are not counted.
+    pub fn execute_with_progress(
+        &mut self,
+        target: &str,
+        mut progress: impl FnMut(f32),
+    ) -> Result<SolverStatus, SolverError> {
+        let node = match self.graph.get_node(target) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(target.into())),
+        };
+        let to_run = self.plan(&[node])?;
+
+        let mut last = SolverStatus::Executed;
+        for (done, node) in to_run.iter().enumerate() {
+            last = self.run_node(*node, &mut || Ok(()))?;
+            progress((done + 1) as f32 / to_run.len() as f32);
+        }
+        Ok(last)
+    }
+
+    fn run_all(&mut self, to_run: &[&'a dyn NodeRunner]) -> Result<SolverStatus, SolverError> {
+        self.run_all_checked(to_run, &mut || Ok(()))
+    }
+
+    /// runs the given tasks in order, `check` is called before each task is run and may
+    /// stop the execution by returning an error.
+    fn run_all_checked(
+        &mut self,
+        to_run: &[&'a dyn NodeRunner],
+        check: &mut dyn FnMut() -> Result<(), SolverError>,
+    ) -> Result<SolverStatus, SolverError> {
+        let mut last = SolverStatus::Executed;
+        for node in to_run {
+            last = self.run_node(*node, check)?;
+        }
+
+        // status of the last task, the one requested
+        Ok(last)
+    }
+
+    /// runs one task, unless disabled or its outputs are already computed
+    fn run_node(
+        &mut self,
+        node: &'a dyn NodeRunner,
+        check: &mut dyn FnMut() -> Result<(), SolverError>,
+    ) -> Result<SolverStatus, SolverError> {
+        // all outputs already computed during this solve, nothing to do
+        let outs = node.get_outs();
+        if !outs.is_empty() && outs.iter().all(|out| self.lookup(out).is_some()) {
+            return Ok(SolverStatus::Cached);
+        }
+
+        if !self.graph.is_enabled(node.get_name()) {
+            return Ok(SolverStatus::Skipped);
+        }
+
+        check()?;
+        self.current_node = Some(node.get_name().into());
+        let mut status = node.run(self);
+        for _ in 0..self.graph.get_retry(node.get_name()) {
+            if status.is_ok() {
+                break;
+            }
+            status = node.run(self);
+        }
+        let status = status.map_err(|e| SolverError::InNode {
+            node: node.get_name().into(),
+            source: Box::new(e),
+        });
+        self.current_node = None;
+
+        let status = status?;
+        if let SolverStatus::Executed = status {
+            *self.run_counts.entry(node.get_name().into()).or_insert(0) += 1;
+        }
+        if self.check_outputs {
+            for out in outs {
+                if !self.cache.contains_key(out) && !self.missing_outputs.contains(out) {
+                    self.missing_outputs.push(out.clone());
+                }
+            }
         }
+        Ok(status)
+    }
 
-        Ok(SolverStatus::Executed)
+    /// Prepares the execution of a task and its prerequisites, one task at a time. Each
+    /// step of the returned iterator runs the next task, in dependency order, and yields
+    /// its name and status. After an error no more tasks are run.
+    pub fn step_iter<'s>(&'s mut self, target: &str) -> Stepper<'s, 'a, 'b> {
+        let planned = match self.graph.get_node(target) {
+            Some(node) => self.plan(&[node]),
+            None => Err(SolverError::NodeNotFound(target.into())),
+        };
+        let (pending, error) = match planned {
+            Ok(to_run) => (to_run, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        Stepper {
+            solver: self,
+            pending: pending.into_iter(),
+            error,
+        }
     }
 
     /// Check if the input is still valid. This function is used
@@ -584,6 +2917,7 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
     /// if all inputs are cached and equal to current values, and a cached
     /// output is available. The output will be considered valid and the computation
     /// skipped
+    #[allow(clippy::ptr_arg)]
     pub fn input_is_new<T>(&self, new_value: &T, name: &String) -> bool
     where
         T: Clone + Comparable + 'static,
@@ -601,10 +2935,7 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
         T: Clone + Comparable + 'static,
     {
         // which asset satisfies this input?
-        let provider = match self.get_binding(name) {
-            Ok(asset) => asset,
-            _ => name,
-        };
+        let provider = self.input_source(name);
 
         // retrieve from last cache cache
         match self.last_cache.get_value::<T>(provider) {
@@ -619,13 +2950,29 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
         }
     }
 
-    /// function to decide whenever the set of values is still valid or the producing node of
-    /// any of the values needs to be executed
-    pub fn use_old_ouput<T: AsRef<str>>(&mut self, ouputs: &[T]) -> bool {
-        for out in ouputs {
-            let name: String = (*out).as_ref().into();
-            if let Some(x) = self.last_cache.get(&name) {
-                self.cache.insert(name, Rc::clone(x));
+    /// Checks a set of candidate input values against the cache of the previous solve,
+    /// returns the names of the inputs which changed and would trigger a recomputation.
+    pub fn changed_inputs<T>(&self, candidates: &[(&str, T)]) -> Vec<String>
+    where
+        T: Clone + Comparable + 'static,
+    {
+        candidates
+            .iter()
+            .filter(|(name, value)| self.input_is_new_str(value, name))
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// function to decide whenever the set of values is still valid or the producing node of
+    /// any of the values needs to be executed
+    pub fn use_old_ouput<T: AsRef<str>>(&mut self, ouputs: &[T]) -> bool {
+        if !self.caching {
+            return false;
+        }
+        for out in ouputs {
+            let name: String = self.graph.current_name((*out).as_ref()).into();
+            if let Some(x) = self.last_cache.get(&name) {
+                self.cache.insert(name, Rc::clone(x));
             } else {
                 return false;
             }
@@ -636,6 +2983,177 @@ impl<'a, 'b> GraphSolver<'a, 'b> {
     pub fn get_values(&self) -> &ValuesCache {
         &self.cache
     }
+
+    /// names of the assets computed or fed during this solve, sorted
+    pub fn cached_assets(&self) -> impl Iterator<Item = &str> {
+        self.cache.keys().map(|name| name.as_str())
+    }
+
+    /// number of assets computed or fed during this solve
+    pub fn cached_len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Moves an asset out of the cache of this solve. The value is not cloned unless it is
+    /// still shared (i.e. it was kept from the previous solve or saved under several
+    /// names). Further reads of the asset fail with `SolverError::AssetNotCreated`, so this
+    /// is meant for large values with a single consumer.
+    pub fn take_value<T>(&mut self, name: &str) -> Result<T, SolverError>
+    where
+        T: Clone + 'static,
+    {
+        let ptr = match self.cache.remove(name) {
+            Some(ptr) => ptr,
+            None => return Err(SolverError::AssetNotCreated(name.into())),
+        };
+        match ptr.downcast::<T>() {
+            Ok(value) => Ok(Rc::try_unwrap(value).unwrap_or_else(|shared| (*shared).clone())),
+            Err(ptr) => {
+                self.cache.insert(name.into(), ptr);
+                Err(SolverError::AssetWrongType(name.into()))
+            }
+        }
+    }
+
+    /// Handle to an asset, computed when its value is requested with `Lazy::get`. Nothing
+    /// is executed nor checked until then.
+    pub fn lazy<T>(&self, asset: &str) -> Lazy<T>
+    where
+        T: Clone + 'static,
+    {
+        Lazy {
+            asset: asset.into(),
+            ty: std::marker::PhantomData,
+        }
+    }
+
+    /// Reads an asset from the cache of the previous solve only, values computed during this
+    /// solve are ignored. A task can read its own previous output this way, a feedback
+    /// which is not a cycle. `None` if the value is not there or has a different type.
+    pub fn feedback_input<T>(&self, name: &str) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        self.last_cache.get_value::<T>(self.graph.current_name(name)).ok()
+    }
+
+    /// Reads an asset if already available, either computed during this solve or in the
+    /// cache of the previous one. Nothing is executed, `None` is returned if the value is
+    /// not available or has a different type.
+    pub fn peek<T>(&self, name: &str) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        match self.get_value::<T>(name) {
+            // only missing assets are looked up in the previous solve
+            Err(SolverError::AssetNotCreated(_)) => self.last_cache.get_value::<T>(name).ok(),
+            value => value.ok(),
+        }
+    }
+
+    /// Gathers all the outputs of a task, already computed in this solve, keyed by asset name.
+    pub fn outputs_of(&self, producer: &str) -> Result<ValuesCache, SolverError> {
+        let node = match self.graph.get_node(producer) {
+            Some(node) => node,
+            None => return Err(SolverError::NodeNotFound(producer.into())),
+        };
+        let mut outputs = ValuesCache::new();
+        for out in node.get_outs() {
+            match self.lookup(out) {
+                Some(value) => outputs.insert(out.clone(), Rc::clone(value)),
+                None => return Err(SolverError::AssetNotCreated(out.clone())),
+            };
+        }
+        Ok(outputs)
+    }
+
+    /// value computed during this solve, or provided by the base cache of an overlay
+    fn lookup(&self, name: &str) -> Option<&Rc<dyn Any>> {
+        self.cache
+            .get(name)
+            .or_else(|| self.base.and_then(|base| base.get(name)))
+    }
+
+    /// name of the task being executed, if any
+    pub fn current_node(&self) -> Option<&str> {
+        self.current_node.as_deref()
+    }
+
+    /// number of times each task body was actually executed (cache hits are not counted)
+    /// over the lifetime of this solver.
+    pub fn run_counts(&self) -> &Map<String, u32> {
+        &self.run_counts
+    }
+
+    /// Harvests every asset computed so far by this solver, keyed by asset name.
+    /// Values are shared, not copied, with the working cache.
+    pub fn collect_outputs(&self) -> ValuesCache {
+        self.cache.clone()
+    }
+
+    /// Retrieves one of the computed assets by name and type, the typed counterpart
+    /// to `collect_outputs`.
+    pub fn typed_output<T>(&self, name: &str) -> Result<T, SolverError>
+    where
+        T: Clone + 'static,
+    {
+        self.get_value::<T>(name)
+    }
+}
+
+/// Drives the execution of a task one step at a time, see `GraphSolver::step_iter`
+pub struct Stepper<'s, 'a, 'b> {
+    solver: &'s mut GraphSolver<'a, 'b>,
+    pending: std::vec::IntoIter<&'a dyn NodeRunner>,
+    error: Option<SolverError>,
+}
+
+impl<'s, 'a, 'b> Iterator for Stepper<'s, 'a, 'b> {
+    type Item = Result<(String, SolverStatus), SolverError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        let node = self.pending.next()?;
+        match self.solver.run_node(node, &mut || Ok(())) {
+            Ok(status) => Some(Ok((node.get_name().to_string(), status))),
+            Err(e) => {
+                // nothing else can run
+                self.pending = Vec::new().into_iter();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Handle to an asset which may not be computed yet, see `GraphSolver::lazy`
+pub struct Lazy<T> {
+    asset: String,
+    ty: std::marker::PhantomData<T>,
+}
+
+impl<T> Lazy<T>
+where
+    T: Clone + 'static,
+{
+    /// Returns the value of the asset, executing the task producing it (and its
+    /// prerequisites) only if it was not computed yet in this solve.
+    pub fn get(&self, solver: &mut GraphSolver) -> Result<T, SolverError> {
+        if solver.lookup(&self.asset).is_none() {
+            let producer = match solver.graph.what_provides(&self.asset) {
+                AssetProvider::Node(node) => node.get_name().to_string(),
+                AssetProvider::Preset(name) => name.clone(),
+                AssetProvider::None => return Err(SolverError::AssetNotProduced(self.asset.clone())),
+            };
+            solver.execute(&producer)?;
+        }
+        solver.get_value::<T>(&self.asset)
+    }
+
+    pub fn asset(&self) -> &str {
+        &self.asset
+    }
 }
 
 impl<'a, 'b> Cache for GraphSolver<'a, 'b> {
@@ -643,13 +3161,18 @@ impl<'a, 'b> Cache for GraphSolver<'a, 'b> {
     where
         T: Clone + 'static,
     {
-        if let Some(ptr) = self.cache.get(name) {
+        if let Some(ptr) = self.lookup(name) {
             if let Some(x) = ptr.as_ref().downcast_ref::<T>() {
+                #[cfg(feature = "metrics")]
+                self.clone_count.set(self.clone_count.get() + 1);
                 return Ok(x.clone());
             } else {
                 return Err(SolverError::AssetWrongType(name.into()));
             }
         }
+        if let Some(existing) = self.graph.aliases.get(name) {
+            return self.get_value(existing);
+        }
         Err(SolverError::AssetNotCreated(name.into()))
     }
 
@@ -664,14 +3187,41 @@ impl<'a, 'b> Cache for GraphSolver<'a, 'b> {
     where
         T: Clone + 'static,
     {
-        let ptr: Rc<Any> = Rc::new(value);
+        let name = self.graph.current_name(name);
+        if self.check_overwrites {
+            if let Some(old) = self.cache.get(name) {
+                if old.as_ref().type_id() != TypeId::of::<T>() {
+                    self.warnings.push(format!(
+                        "asset {} overwritten with a value of a different type ({})",
+                        name,
+                        std::any::type_name::<T>()
+                    ));
+                }
+            }
+        }
+        let ptr: Rc<dyn Any> = Rc::new(value);
         self.cache.insert(name.into(), ptr);
     }
+
+    fn get_raw(&self, name: &str) -> Option<Rc<dyn Any>> {
+        self.lookup(name).cloned()
+    }
+
+    fn save_raw(&mut self, name: &str, value: Rc<dyn Any>) {
+        self.cache.save_raw(name, value);
+    }
+
+    fn merge_cache(&mut self, other: ValuesCache, policy: MergePolicy) {
+        self.cache.merge_cache(other, policy);
+    }
 }
 
 impl<'a, 'b> Drop for GraphSolver<'a, 'b> {
     fn drop(&mut self) {
-        mem::swap(&mut self.cache, &mut self.last_cache);
+        for asset in &self.overrides {
+            self.cache.remove(asset);
+        }
+        mem::swap(&mut self.cache, self.last_cache);
     }
 }
 
@@ -682,6 +3232,36 @@ impl<'a, 'b> Drop for GraphSolver<'a, 'b> {
 mod tests {
     use super::*;
 
+    fn get_example_graph() -> Graph {
+        let mut g = Graph::new();
+
+        g.add_node(create_node!(
+                gen_one () ->  (one: u32) {
+                    one = 1u32;
+                }
+            )).unwrap();
+
+        g.add_node(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    plusone = one + 1u32;
+                }
+            )).unwrap();
+
+        g.add_node(create_node!(
+                the_one_task  (one: u32, plusone : u32) -> (last_value: f32) {
+                    last_value = (one + plusone) as f32;
+                }
+            )).unwrap();
+
+        g.bind_asset("gen_one::one", "plus_one::one")
+            .expect("binding must be doable");
+        g.bind_asset("plus_one::plusone", "the_one_task::plusone")
+            .expect("binding must be doable");
+        g.bind_asset("gen_one::one", "the_one_task::one")
+            .expect("binding must be doable");
+        g
+    }
+
     #[test]
     fn graph() {
         let mut g = Graph::new();
@@ -710,198 +3290,2493 @@ mod tests {
 
         let mut cache = ValuesCache::new();
         let mut solver = GraphSolver::new(&g, &mut cache);
-
-        let x = g.get_node("one").expect("must exist");
-        assert!(x.run(&mut solver).is_ok());
-        let y = g.get_node("two").expect("must exist");
-        assert!(y.run(&mut solver).is_ok());
+
+        let x = g.get_node("one").expect("must exist");
+        assert!(x.run(&mut solver).is_ok());
+        let y = g.get_node("two").expect("must exist");
+        assert!(y.run(&mut solver).is_ok());
+    }
+
+    #[test]
+    fn node_not_ready() {
+        let g = Graph::new();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        let node = create_node!(
+            test (i : u32) -> (x: u32) {
+                x = i +1;
+            }
+        );
+
+        assert!(node.run(&mut solver).is_err());
+    }
+
+    #[test]
+    fn construct_nodes() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(no_output ( i : u32, j : u32) -> ()
+                                 {
+                                     println!("{} {}", i, j);
+                                 })).unwrap();
+
+        g.add_node(create_node!(no_input  ( ) -> ( x : f32, y : f64)
+                                 {
+                                     x = 1.0f32;
+                                     y = 4.0;
+                                 })).unwrap();
+
+        g.add_node(create_node!(both_input_and_output ( i : u32, j : u32)
+                                 -> ( x : f32, y : f64)
+                                 {
+                                     x = i as f32;
+                                     y = j as f64;
+                                 })).unwrap();
+    }
+
+    #[test]
+    fn solver() {
+        let g = Graph::new();
+        let mut cache = ValuesCache::new();
+        let mut s = GraphSolver::new(&g, &mut cache);
+
+        let a: i32 = 1;
+        s.save_value_str("a", a);
+
+        assert!(s.get_value::<i32>("a").is_ok());
+        assert!(s.get_value::<u32>("a").is_err());
+        assert!(s.get_value::<u32>("j").is_err());
+
+        println!(" hey");
+    }
+
+    #[test]
+    fn graph_with_assets() {
+        let mut g = Graph::new();
+
+        g.add_node(create_node!(
+                gen_one () ->  (one: u32) {
+                    println!("gen one");
+                    one = 1u32;
+                }
+            )).unwrap();
+
+        g.add_node(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    println!("plusone");
+                    plusone = one + 1u32;
+                }
+            )).unwrap();
+
+        g.add_node(create_node!(
+                the_one_task  (one: u32, plusone : u32) -> (last_value: f32) {
+                    println!("the one task");
+                    last_value = (one + plusone) as f32;
+                }
+            )).unwrap();
+
+        //do connection
+        g.bind_asset("gen_one::one", "plus_one::one")
+            .expect("binding must be doable");
+        g.bind_asset("plus_one::plusone", "the_one_task::plusone")
+            .expect("binding must be doable");
+        g.bind_asset("gen_one::one", "the_one_task::one")
+            .expect("binding must be doable");
+
+        g.get_binding_str("plus_one::one")
+            .expect("binding must be set");
+        g.get_binding_str("the_one_task::plusone")
+            .expect("binding must be set");
+        g.get_binding_str("the_one_task::one")
+            .expect("binding must be set");
+
+        let mut cache = ValuesCache::new();
+
+        for _ in 0..10 {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute("nop").is_err());
+            solver.execute("the_one_task").expect("could not execute");
+            solver
+                .get_value::<f32>("the_one_task::last_value")
+                .expect("could not retrieve result");
+        }
+
+        assert!(
+            cache
+                .get_value::<f32>("the_one_task::last_value")
+                .expect("must be f32") == 3f32
+        );
+    }
+
+    #[test]
+    fn terminal_names() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(sink_2 (name : u32) -> () { println!("{}", name); })).unwrap();
+        g.add_node(create_node!(sink_1 (input : u32) -> () { println!("{}", input); })).unwrap();
+        g.add_node(create_node!(no_input () -> (o : u32) { o = 1234; })).unwrap();
+        assert!(g.terminal_names() == vec!["sink_1", "sink_2"]);
+
+        g.set_ordering(NodeOrdering::Insertion);
+        assert!(g.terminal_names() == vec!["sink_2", "sink_1"]);
+    }
+
+    #[test]
+    fn terminals() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(sink_1 ( input : u32) -> ()
+                                 {
+                                     println!("sink 1 {}", input);
+                                 })).unwrap();
+
+        g.add_node(create_node!(sink_2 ( name : u32) -> ()
+                                 {
+                                     println!("sink 2 {}", name);
+                                 })).unwrap();
+
+        g.add_node(create_node!(no_input () -> ( o : u32)
+                                 {
+                                     o =  1234;
+                                     println!("produce {}", o);
+                                 })).unwrap();
+
+        g.bind_asset("no_input::o", "sink_1::input")
+            .expect("binding must be doable");
+        g.bind_asset("no_input::o", "sink_2::name")
+            .expect("binding must be doable");
+
+        // slices have no size...
+        // assert!(g.get_terminals().size() == 1);
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute_terminals().expect("this should run");
+        }
+
+        assert!(cache.get_value::<u32>("no_input::o").expect("must be f32") == 1234);
+    }
+
+    #[test]
+    fn freestanding_assets() {
+        let mut g = Graph::new();
+
+        g.add_node(create_node!(node1 ( a : u32) -> ()
+                                 { }))
+            .unwrap();
+        assert!(g.get_unbound_assets().len() == 1);
+
+        g.define_freestanding_asset("startvalue", 0u32).expect("redeclared?");
+
+        g.bind_asset("startvalue", "node1::a")
+            .expect("binding must be doable");
+
+        println!("{:?}", g.get_freestanding_assets());
+        println!("{:?}", g.get_unbound_assets());
+        assert!(g.get_unbound_assets().is_empty());
+    }
+
+    #[test]
+    fn unbound_assets() {
+        let mut g = Graph::new();
+        assert!(g.get_unbound_assets().is_empty());
+
+        g.add_node(create_node!(consumer ( a : u32, b: i32, c: f32) -> ()
+                                 { }))
+            .unwrap();
+        assert!(g.get_unbound_assets().len() == 3);
+
+        g.add_node(create_node!(producer ( ) -> ( v: i32 )
+                                 { v = 1; }))
+            .unwrap();
+        assert!(g.get_unbound_assets().len() == 3);
+
+        g.bind_asset("producer::v", "consumer::b")
+            .expect("binding must be doable");
+        println!("{:?}", g.get_unbound_assets());
+        assert!(g.get_unbound_assets().len() == 2);
+    }
+
+    #[test]
+    fn collect_outputs() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("the_one_task").expect("could not execute");
+
+        let outputs = solver.collect_outputs();
+        assert!(outputs.contains_key("gen_one::one"));
+        assert!(outputs.contains_key("plus_one::plusone"));
+        assert!(outputs.contains_key("the_one_task::last_value"));
+
+        assert!(solver.typed_output::<f32>("the_one_task::last_value").expect("must be f32") == 3f32);
+        assert!(solver.typed_output::<u32>("the_one_task::last_value").is_err());
+    }
+
+    #[test]
+    fn topology_eq() {
+        let g = get_example_graph();
+
+        // same graph, nodes and bindings declared in a different order
+        let mut h = Graph::new();
+        h.add_node(create_node!(
+                the_one_task  (one: u32, plusone : u32) -> (last_value: f32) {
+                    last_value = (one + plusone) as f32 * 2.0;
+                }
+            )).unwrap();
+        h.add_node(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    plusone = one;
+                }
+            )).unwrap();
+        h.add_node(create_node!(
+                gen_one () ->  (one: u32) {
+                    one = 5u32;
+                }
+            )).unwrap();
+        h.bind_asset("gen_one::one", "the_one_task::one")
+            .expect("binding must be doable");
+        h.bind_asset("plus_one::plusone", "the_one_task::plusone")
+            .expect("binding must be doable");
+        assert!(!g.topology_eq(&h));
+
+        h.bind_asset("gen_one::one", "plus_one::one")
+            .expect("binding must be doable");
+        assert!(g.topology_eq(&h));
+        assert!(h.topology_eq(&g));
+    }
+
+    #[test]
+    fn orphan_nodes() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        g.add_node(create_node!(consumer (a : u32) -> ()
+                                 { }))
+            .unwrap();
+        g.bind_asset("start", "consumer::a")
+            .expect("binding must be doable");
+
+        // the_one_task output is not consumed by anyone
+        assert!(g.orphan_nodes() == vec!["the_one_task".to_string()]);
+
+        g.add_node(create_node!(disconnected () -> (x : u32)
+                                 { x = 1; }))
+            .unwrap();
+        let orphans = g.orphan_nodes();
+        assert!(orphans.len() == 2);
+        assert!(orphans.contains(&"disconnected".to_string()));
+        assert!(!orphans.contains(&"start".to_string()));
+
+        g.add_node(create_node!(log (from disconnected) -> ()
+                                 { println!("{:?}", disconnected); }))
+            .unwrap();
+        assert!(g.orphan_nodes() == vec!["the_one_task".to_string()]);
+    }
+
+    #[test]
+    fn sources() {
+        let mut g = get_example_graph();
+        assert!(g.sources() == vec!["gen_one"]);
+
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        g.add_node(create_node!(log (from plus_one) -> () { println!("{:?}", plus_one.keys()); }))
+            .unwrap();
+        assert!(g.sources() == vec!["gen_one", "start"]);
+    }
+
+    fn get_chain_graph(max: u32) -> Graph {
+        let mut g = Graph::new();
+        for i in 1..max {
+            let name: String = format!("task{}", i);
+            g.add_node(create_node!(name: name, ( input : u32) -> (output : u32)
+                                     {
+                                         output = input + 1;
+                                     }))
+                .unwrap();
+        }
+        for i in 1..max - 1 {
+            let src = format!("task{}::output", i);
+            let sink = format!("task{}::input", i + 1);
+            g.bind_asset(src.as_str(), sink.as_str())
+                .expect("binding must be doable");
+        }
+        g.define_freestanding_asset("start", 0u32).expect("could not create asset");
+        g.bind_asset("start", "task1::input")
+            .expect("could not bind first task to start value");
+        g
+    }
+
+    #[test]
+    fn reachable_assets() {
+        let mut g = get_chain_graph(5);
+        g.add_node(create_node!(other (a : u32, b : u32) -> (c : u32)
+                                 { c = a + b; }))
+            .unwrap();
+        g.bind_asset("task2::output", "other::a").expect("binding must be doable");
+
+        let reachable = g.reachable_assets(&["start"]);
+        assert!(reachable == vec![
+            "start::value".to_string(),
+            "task1::output".to_string(),
+            "task2::output".to_string(),
+            "task3::output".to_string(),
+            "task4::output".to_string(),
+        ]);
+
+        let reachable = g.reachable_assets(&["task3::output"]);
+        assert!(reachable == vec!["task3::output".to_string(), "task4::output".to_string()]);
+    }
+
+    #[test]
+    fn resolve_input() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        g.add_node(create_node!(consumer (a : u32, b : u32, c : u32) -> ()
+                                 { }))
+            .unwrap();
+        g.bind_asset("start", "consumer::a").expect("binding must be doable");
+        g.bind_asset("plus_one::one", "consumer::b").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.resolve_input("plus_one::one").expect("is bound") == "gen_one::one");
+        assert!(solver.resolve_input("consumer::a").expect("is bound") == "start::value");
+        assert!(solver.resolve_input("consumer::b").expect("is bound") == "gen_one::one");
+        assert!(matches!(solver.resolve_input("consumer::c"), Err(SolverError::AssetUnbound(_))));
+        assert!(matches!(solver.resolve_input("consumer::d"), Err(SolverError::AssetNotDeclared(_))));
+    }
+
+    #[test]
+    fn pipeline() {
+        let mut g = Graph::new();
+        pipeline!(&mut g,
+            read () -> (text: String) {
+                text = "20".to_string();
+            },
+            parse (text: String) -> (value: u32) {
+                value = text.parse::<u32>().map_err(SolverError::task)?;
+            },
+            double (value: u32) -> (result: u32) {
+                result = value * 2;
+            }
+        ).expect("pipeline must be valid");
+
+        assert!(g.node_count() == 3);
+        assert!(g.get_binding_str("double::value") == Some(&"parse::value".to_string()));
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("double").expect("could not execute");
+        assert!(solver.get_value::<u32>("double::result").expect("must be u32") == 40);
+
+        let mut g = Graph::new();
+        let res = pipeline!(&mut g,
+            first () -> (value: u32) { value = 1; },
+            second (value: f32) -> (half: f32) { half = value / 2.0; }
+        );
+        assert!(matches!(res, Err(GraphError::TypeMismatch(_, _))));
+        assert!(g.is_empty());
+    }
+
+    #[test]
+    fn default_values() {
+        let mut g = get_example_graph();
+        g.add_node(create_node!(scale (value : u32, factor : u32) -> (scaled : u32)
+                                 { scaled = value * factor; }))
+            .unwrap();
+        g.bind_asset("plus_one::plusone", "scale::value").expect("binding must be doable");
+        assert!(matches!(g.set_default("scale::nothing", 3u32), Err(GraphError::UndefinedAssetSlot(_))));
+        match g.set_default("scale::factor", 3f32) {
+            Err(GraphError::TypeMismatch(src, sink)) => {
+                assert!(src == "f32");
+                assert!(sink == "scale::factor");
+            }
+            _ => panic!("default must be rejected"),
+        }
+        g.set_default("scale::factor", 3u32).expect("input exists");
+        g.set_default("scale::value", 100u32).expect("input exists");
+
+        let mut cache = ValuesCache::new();
+        {
+            // bound inputs ignore the default
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute("scale").expect("could not execute");
+            assert!(solver.get_value::<u32>("scale::scaled").expect("must be u32") == 6);
+        }
+        {
+            // fed values have preference
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.feed_input("scale::factor", 5u32);
+            solver.execute("scale").expect("could not execute");
+            assert!(solver.get_value::<u32>("scale::scaled").expect("must be u32") == 10);
+        }
+    }
+
+    #[test]
+    fn node_depth() {
+        let mut g = get_example_graph();
+        assert!(g.node_depth("gen_one").unwrap() == 0);
+        assert!(g.node_depth("plus_one").unwrap() == 1);
+        // gen_one is also bound directly, the longest path counts
+        assert!(g.node_depth("the_one_task").unwrap() == 2);
+        assert!(matches!(g.node_depth("nop"), Err(GraphError::NodeNotFound(_))));
+
+        let chain = get_chain_graph(100);
+        assert!(chain.node_depth("task99").unwrap() == 99);
+
+        g.add_node(create_node!(loop_a (a : u32) -> (b : u32) { b = a; })).unwrap();
+        g.add_node(create_node!(loop_b (b : u32) -> (a : u32) { a = b; })).unwrap();
+        g.bind_asset("loop_a::b", "loop_b::b").expect("binding must be doable");
+        g.bind_asset("loop_b::a", "loop_a::a").expect("binding must be doable");
+        assert!(matches!(g.node_depth("gen_one"), Err(GraphError::CycleDetected(_))));
+    }
+
+    #[test]
+    fn warnings() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(clamp |solver| (value : i32) -> (clamped : u32) {
+            if value < 0 {
+                solver.warn(format!("negative value {} clamped to 0", value));
+            }
+            clamped = value.max(0) as u32;
+        }))
+        .unwrap();
+        g.add_node(create_node!(parse |solver| (text : String) -> (number : u32) => {
+            text.parse::<u32>().or_else(|_| {
+                solver.warn(format!("{:?} is not a number", text));
+                Ok(0)
+            })
+        }))
+        .unwrap();
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.feed_input("clamp::value", -3i32);
+        solver.feed_input("parse::text", "three".to_string());
+        solver.execute("clamp").expect("could not execute");
+        solver.execute("parse").expect("could not execute");
+        assert!(solver.get_value::<u32>("clamp::clamped").expect("must be u32") == 0);
+        assert!(solver.get_value::<u32>("parse::number").expect("must be u32") == 0);
+        assert!(solver.warnings() == ["negative value -3 clamped to 0".to_string(),
+                                      "\"three\" is not a number".to_string()]);
+    }
+
+    #[test]
+    fn preflight() {
+        let mut g = get_example_graph();
+        g.preflight("the_one_task").expect("graph is runnable");
+        assert!(matches!(g.preflight("nop").unwrap_err()[0], GraphError::NodeNotFound(_)));
+
+        g.add_node(create_node!(consumer (a : u32, b : u32) -> ()
+                                 { }))
+            .unwrap();
+        g.bind_asset("plus_one::plusone", "consumer::a").expect("binding must be doable");
+        g.add_node(create_node!(loop_a (a : u32) -> (b : u32) { b = a; })).unwrap();
+        g.add_node(create_node!(loop_b (b : u32) -> (a : u32) { a = b; })).unwrap();
+        g.bind_asset("loop_a::b", "loop_b::b").expect("binding must be doable");
+        g.bind_asset("loop_b::a", "loop_a::a").expect("binding must be doable");
+
+        let errors = g.preflight("consumer").unwrap_err();
+        assert!(errors.len() == 2);
+        assert!(errors.iter().any(|e| matches!(e, GraphError::CycleDetected(_))));
+        assert!(errors.iter().any(|e| matches!(e, GraphError::UndefinedAssetSlot(input) if input == "consumer::b")));
+
+        g.set_default("consumer::b", 1u32).expect("input exists");
+        assert!(g.preflight("consumer").unwrap_err().len() == 1);
+    }
+
+    #[test]
+    fn override_freestanding() {
+        let g = get_chain_graph(4);
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(matches!(solver.override_freestanding("nop", 1u32),
+                             Err(SolverError::AssetNotDeclared(_))));
+            assert!(matches!(solver.override_freestanding("start", 1f32),
+                             Err(SolverError::AssetWrongType(_))));
+            solver.override_freestanding("start", 10u32).expect("start is freestanding");
+            solver.execute("task3").expect("could not execute");
+            assert!(solver.get_value::<u32>("task3::output").expect("must be u32") == 13);
+            assert!(solver.run_counts().get("start").is_none());
+        }
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute("task3").expect("could not execute");
+            assert!(solver.get_value::<u32>("task3::output").expect("must be u32") == 3);
+        }
+    }
+
+    #[test]
+    fn cancellation() {
+        use std::sync::Arc;
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let mut g = get_chain_graph(5);
+        let flag = cancel.clone();
+        g.replace_node(create_node!(
+                task2 [flag: Arc<AtomicBool>] (input : u32) -> (output : u32) {
+                    flag.store(true, Ordering::SeqCst);
+                    output = input + 1;
+                }
+            )).expect("same signature");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        match solver.execute_cancellable("task4", &cancel) {
+            Err(SolverError::Cancelled) => {}
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(solver.get_value::<u32>("task2::output").expect("must be u32") == 2);
+        assert!(solver.get_value::<u32>("task3::output").is_err());
+
+        drop(solver);
+
+        cancel.store(false, Ordering::SeqCst);
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.set_caching(false);
+        assert!(matches!(solver.execute_cancellable("task1", &cancel), Ok(SolverStatus::Executed)));
+    }
+
+    #[test]
+    fn bind_by_matching_names() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(load () -> (image : u32, width : u32)
+                                 { image = 1; width = 2; }))
+            .unwrap();
+        g.add_node(create_node!(measure () -> (width : u32)
+                                 { width = 3; }))
+            .unwrap();
+        g.add_node(create_node!(blur (image : u32, width : u32, radius : u32) -> (blurred : u32)
+                                 { blurred = image + width + radius; }))
+            .unwrap();
+        g.add_node(create_node!(save (blurred : u32) -> ()
+                                 { println!("{}", blurred); }))
+            .unwrap();
+
+        let bound = g.bind_by_matching_names();
+        assert!(bound == vec![
+            ("load::image".to_string(), "blur::image".to_string()),
+            ("blur::blurred".to_string(), "save::blurred".to_string()),
+        ]);
+        // width is ambiguous, radius has no candidate
+        assert!(g.get_binding_str("blur::width").is_none());
+        assert!(g.get_binding_str("blur::radius").is_none());
+        assert!(g.bind_by_matching_names().is_empty());
+    }
+
+    #[test]
+    fn execute_map() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        let text = solver
+            .execute_map("plus_one", "plusone", |value: u32| format!("value: {}", value))
+            .expect("could not execute");
+        assert!(text == "value: 2");
+        assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+        assert!(matches!(solver.execute_map("plus_one", "nothing", |value: u32| value),
+                         Err(SolverError::AssetNotProduced(_))));
+    }
+
+    #[test]
+    fn check_overwrites() {
+        let g = Graph::new();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.save_value_str("asset", 1u32);
+        solver.save_value_str("asset", "text".to_string());
+        assert!(solver.warnings().is_empty());
+
+        solver.set_check_overwrites(true);
+        solver.save_value_str("asset", "other".to_string());
+        assert!(solver.warnings().is_empty());
+        solver.save_value_str("asset", 2u32);
+        assert!(solver.warnings().len() == 1);
+        assert!(solver.warnings()[0].starts_with("asset asset overwritten"));
+    }
+
+    #[test]
+    fn assets_of() {
+        let g = get_example_graph();
+        assert!(g.inputs_of("the_one_task").unwrap() == ["the_one_task::one", "the_one_task::plusone"]);
+        assert!(g.outputs_of("the_one_task").unwrap() == ["the_one_task::last_value"]);
+        assert!(g.inputs_of("gen_one").unwrap().is_empty());
+        assert!(g.inputs_of("nop").is_none());
+        assert!(g.outputs_of("nop").is_none());
+    }
+
+    #[test]
+    fn cone_size() {
+        let g = get_example_graph();
+        // gen_one is reached twice, but counted once
+        assert!(g.cone_size("the_one_task").unwrap() == 3);
+        assert!(g.cone_size("gen_one").unwrap() == 1);
+        assert!(matches!(g.cone_size("nop"), Err(GraphError::NodeNotFound(_))));
+
+        let chain = get_chain_graph(10);
+        assert!(chain.cone_size("task9").unwrap() == 10);
+    }
+
+    #[test]
+    fn bind_asset_report() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        g.add_node(create_node!(consumer (a : u32, b : u32) -> ()
+                                 { }))
+            .unwrap();
+
+        let info = g.bind_asset_report("start", "consumer::a").expect("binding must be doable");
+        assert!(info.resolved_source.ends_with("::value"));
+        assert!(info.resolved_source == "start::value");
+        assert!(info.is_freestanding);
+
+        let info = g.bind_asset_report("gen_one::one", "consumer::b").expect("binding must be doable");
+        assert!(info == BindingInfo {
+            resolved_source: "gen_one::one".to_string(),
+            is_freestanding: false,
+        });
+    }
+
+    #[test]
+    fn rebind_graph() {
+        use std::cell::Cell;
+        let runs = Rc::new(Cell::new(0u32));
+
+        let producer: Rc<dyn NodeRunner> = Rc::new(create_node!(
+                produce [runs: Rc<Cell<u32>>] () -> (value : u32) {
+                    runs.set(runs.get() + 1);
+                    value = 21;
+                }
+            ));
+
+        let mut a = Graph::new();
+        a.add_shared_node(producer.clone()).unwrap();
+
+        let mut b = Graph::new();
+        b.add_shared_node(producer).unwrap();
+        b.add_node(create_node!(double (value : u32) -> (doubled : u32)
+                                 { doubled = value * 2; }))
+            .unwrap();
+        b.bind_asset("produce::value", "double::value").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&a, &mut cache);
+        solver.execute("produce").expect("could not execute");
+        assert!(solver.execute("double").is_err());
+
+        solver.rebind_graph(&b);
+        solver.execute("double").expect("could not execute");
+        assert!(solver.get_value::<u32>("double::doubled").expect("must be u32") == 42);
+        assert!(runs.get() == 1);
+    }
+
+    #[test]
+    fn finalize() {
+        let mut g = get_example_graph();
+        g.finalize().expect("no collisions");
+
+        g.add_node(Node::new(
+            "twice",
+            |_: &mut GraphSolver| Ok(SolverStatus::Executed),
+            vec!["gen_one::one".to_string()],
+            vec!["twice::out".to_string(), "twice::out".to_string()],
+        ))
+        .unwrap();
+
+        let errors = g.finalize().unwrap_err();
+        assert!(errors.len() == 2);
+        assert!(errors.iter().any(|e| matches!(e, GraphError::DuplicateOutput(out) if out == "twice::out")));
+        assert!(errors.iter().any(|e| matches!(e, GraphError::RedeclaredAsset(input) if input == "gen_one::one")));
+    }
+
+    #[test]
+    fn merge_caches() {
+        let get_caches = || {
+            let mut first = ValuesCache::new();
+            first.save_value_str("a::value", 1u32);
+            first.save_value_str("shared::value", 1u32);
+            let mut second = ValuesCache::new();
+            second.save_value_str("b::value", 2u32);
+            second.save_value_str("shared::value", 2u32);
+            (first, second)
+        };
+
+        let (mut first, second) = get_caches();
+        first.merge_cache(second, MergePolicy::KeepExisting);
+        assert!(first.len() == 3);
+        assert!(first.get_value::<u32>("b::value").expect("must be u32") == 2);
+        assert!(first.get_value::<u32>("shared::value").expect("must be u32") == 1);
+
+        let (mut first, second) = get_caches();
+        first.merge_cache(second, MergePolicy::Overwrite);
+        assert!(first.len() == 3);
+        assert!(first.get_value::<u32>("a::value").expect("must be u32") == 1);
+        assert!(first.get_value::<u32>("shared::value").expect("must be u32") == 2);
+    }
+
+    #[test]
+    fn execution_layers() {
+        let g = get_example_graph();
+        let layers = g.execution_layers().expect("no cycles");
+        assert!(layers == vec![vec!["gen_one"], vec!["plus_one"], vec!["the_one_task"]]);
+
+        let mut g = Graph::new();
+        g.add_node(create_node!(top () -> (v : u32) { v = 1; })).unwrap();
+        g.add_node(create_node!(left (v : u32) -> (l : u32) { l = v; })).unwrap();
+        g.add_node(create_node!(right (v : u32) -> (r : u32) { r = v; })).unwrap();
+        g.add_node(create_node!(bottom (l : u32, r : u32) -> () { println!("{}", l + r); }))
+            .unwrap();
+        g.bind_asset("top::v", "left::v").expect("binding must be doable");
+        g.bind_asset("top::v", "right::v").expect("binding must be doable");
+        g.bind_asset("left::l", "bottom::l").expect("binding must be doable");
+        g.bind_asset("right::r", "bottom::r").expect("binding must be doable");
+
+        let layers = g.execution_layers().expect("no cycles");
+        assert!(layers == vec![vec!["top"], vec!["left", "right"], vec!["bottom"]]);
+        assert!(Graph::new().execution_layers().expect("no cycles").is_empty());
+    }
+
+    #[test]
+    fn max_depth() {
+        let g = get_chain_graph(1000);
+        let mut cache = ValuesCache::new();
+
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_max_depth(100);
+            match solver.execute("task999") {
+                Err(SolverError::DepthExceeded(100)) => {}
+                x => panic!("unexpected result {:?}", x),
+            }
+        }
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_max_depth(100);
+            solver.execute("task50").expect("this should run");
+        }
+    }
+
+    #[test]
+    fn terminals_partial() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(no_input () -> ( o : u32)
+                                 {
+                                     o =  1234;
+                                 })).unwrap();
+        g.add_node(create_node!(runnable ( input : u32) -> ()
+                                 {
+                                     println!("runnable {}", input);
+                                 })).unwrap();
+        g.add_node(create_node!(broken ( missing : u32) -> ()
+                                 {
+                                     println!("broken {}", missing);
+                                 })).unwrap();
+        g.bind_asset("no_input::o", "runnable::input")
+            .expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute_terminals().is_err());
+        }
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            let report = solver.execute_terminals_partial().expect("runnable should run");
+            assert!(report.executed.len() == 1);
+            assert!(report.executed[0].0 == "runnable");
+            assert!(report.skipped == vec!["broken".to_string()]);
+        }
+        assert!(cache.get_value::<u32>("no_input::o").expect("must be u32") == 1234);
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_skip_unsatisfiable(true);
+            assert!(solver.execute_terminals().expect("runnable should run") == SolverStatus::Executed);
+            solver.set_skip_unsatisfiable(false);
+            assert!(solver.execute_terminals().is_err());
+        }
+        assert!(cache.get_value::<u32>("no_input::o").expect("must be u32") == 1234);
+    }
+
+    #[test]
+    fn input_status() {
+        let mut g = get_example_graph();
+        g.add_node(create_node!(consumer (ready : u32, missing : u32, deep : f32) -> ()
+                                 { println!("{} {} {}", ready, missing, deep); }))
+            .unwrap();
+        g.bind_asset("plus_one::plusone", "consumer::ready").expect("binding must be doable");
+        g.bind_asset("the_one_task::last_value", "consumer::deep").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.input_status("consumer") == vec![
+            ("consumer::ready".to_string(), true),
+            ("consumer::missing".to_string(), false),
+            ("consumer::deep".to_string(), true),
+        ]);
+        assert!(solver.input_status("nop").is_empty());
+
+        solver.feed_input("consumer::missing", 3u32);
+        assert!(solver.input_status("consumer").iter().all(|(_, ready)| *ready));
+    }
+
+    #[test]
+    fn rename_assets() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 5u32).expect("redeclared?");
+        g.set_default("plus_one::one", 7u32).expect("input exists");
+        g.rename_assets(|asset| Some(asset.replace("::", ".")));
+
+        assert!(g.get_node("plus_one").unwrap().get_ins() == ["plus_one.one".to_string()]);
+        assert!(g.get_binding_str("the_one_task.plusone") == Some(&"plus_one.plusone".to_string()));
+        assert!(g.get_binding_str("the_one_task::plusone").is_none());
+        assert!(g.get_node("plus_one").unwrap().get_asset_type("plus_one.one") == Some(TypeId::of::<u32>()));
+        assert!(matches!(g.what_provides("gen_one.one"), AssetProvider::Node(_)));
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute("the_one_task").expect("could not execute");
+            assert!(solver.get_value::<f32>("the_one_task.last_value").expect("must be f32") == 3.0);
+            assert!(solver.get_value::<f32>("the_one_task::last_value").is_err());
+        }
+
+        // renames apply on top of previous ones
+        g.rename_assets(|asset| asset.strip_prefix("plus_one.").map(|name| format!("inc.{}", name)));
+        let info = g.bind_asset_report("start", "inc.one").expect("binding must be doable");
+        assert!(info.resolved_source == "start.value");
+        assert!(info.is_freestanding);
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("the_one_task").expect("could not execute");
+        assert!(solver.get_value::<u32>("inc.plusone").expect("must be u32") == 6);
+        assert!(solver.get_value::<f32>("the_one_task.last_value").expect("must be f32") == 7.0);
+    }
+
+    #[test]
+    fn strict() {
+        let colliding = || {
+            Node::new(
+                "twice",
+                |_: &mut GraphSolver| Ok(SolverStatus::Executed),
+                vec![],
+                vec!["twice::out".to_string(), "twice::out".to_string()],
+            )
+        };
+
+        let mut g = Graph::new();
+        g.add_node(colliding()).expect("only checked by finalize");
+        assert!(g.finalize().is_err());
+
+        let mut g = Graph::strict();
+        assert!(matches!(g.add_node(colliding()), Err(GraphError::DuplicateOutput(_))));
+        assert!(g.get_node("twice").is_none());
+
+        g.add_node(create_node!(gen () -> (value : u32) { value = 1; })).unwrap();
+        g.add_node(create_node!(half (value : f32) -> () { println!("{}", value / 2.0); }))
+            .unwrap();
+        assert!(matches!(g.bind_asset("gen::value", "half::value"),
+                         Err(GraphError::TypeMismatch(_, _))));
+        g.set_strict(false);
+        g.bind_asset("gen::value", "half::value").expect("types are not checked");
+    }
+
+    #[test]
+    fn consumers_of_output() {
+        let mut g = get_example_graph();
+        assert!(g.consumers_of_output("gen_one::one") == vec!["plus_one::one", "the_one_task::one"]);
+        assert!(g.consumers_of_output("plus_one::plusone") == vec!["the_one_task::plusone"]);
+        assert!(g.consumers_of_output("the_one_task::last_value").is_empty());
+
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        g.bind_asset("start", "plus_one::one").expect("binding must be doable");
+        assert!(g.consumers_of_output("start") == vec!["plus_one::one"]);
+        assert!(g.consumers_of_output("gen_one::one") == vec!["the_one_task::one"]);
+    }
+
+    #[test]
+    fn into_node() {
+        let get_subgraph = || {
+            let mut sub = Graph::new();
+            sub.add_node(create_node!(double (x : u32) -> (doubled : u32) { doubled = x * 2; }))
+                .unwrap();
+            sub.add_node(create_node!(inc (doubled : u32) -> (result : u32) { result = doubled + 1; }))
+                .unwrap();
+            sub
+        };
+
+        // both assets would be named sub::doubled
+        let clash = get_subgraph().into_node("sub", &["inc::doubled"], &["double::doubled"]);
+        assert!(matches!(clash, Err(GraphError::RedeclaredAsset(name)) if name == "sub::doubled"));
+
+        let mut sub = get_subgraph();
+        sub.bind_asset("double::doubled", "inc::doubled").expect("binding must be doable");
+        let node = sub.into_node("sub", &["double::x"], &["inc::result", "double::doubled"])
+            .expect("no clashing names");
+        assert!(node.get_ins() == ["sub::x".to_string()]);
+        assert!(node.get_outs() == ["sub::result".to_string(), "sub::doubled".to_string()]);
+
+        let mut g = Graph::new();
+        g.define_freestanding_asset("start", 5u32).expect("redeclared?");
+        g.add_node(node).unwrap();
+        g.add_node(create_node!(show (result : u32) -> (text : String) { text = result.to_string(); }))
+            .unwrap();
+        g.bind_asset("start", "sub::x").expect("binding must be doable");
+        g.bind_asset("sub::result", "show::result").expect("binding must be doable");
+        assert!(g.get_node("sub").unwrap().get_asset_type("sub::x") == Some(TypeId::of::<u32>()));
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.run::<String>("show", "text").expect("could not execute") == "11");
+        assert!(solver.get_value::<u32>("sub::doubled").expect("must be u32") == 10);
+    }
+
+    #[test]
+    fn bind_pairs() {
+        let mut g = Graph::new();
+        for name in &["a", "b", "c", "d"] {
+            g.add_node(create_node!(name: name.to_string(), (input : u32) -> (output : u32)
+                                     { output = input + 1; }))
+                .unwrap();
+        }
+        let pair = |src: &str, sink: &str| (format!("{}::output", src), format!("{}::input", sink));
+
+        g.bind_pairs(vec![pair("a", "b"), pair("b", "c"), pair("c", "d")])
+            .expect("bindings must be doable");
+        assert!(g.resolve_source("d::input") == Some("c::output".to_string()));
+        assert!(g.bindings.len() == 3);
+
+        let mut g = Graph::new();
+        for name in &["a", "b", "c"] {
+            g.add_node(create_node!(name: name.to_string(), (input : u32) -> (output : u32)
+                                     { output = input + 1; }))
+                .unwrap();
+        }
+        g.bind_asset("a::output", "b::input").expect("binding must be doable");
+        match g.bind_pairs(vec![pair("b", "c"), pair("c", "nop"), pair("c", "a")]) {
+            Err(GraphError::BindingFailed { index, source }) => {
+                assert!(index == 1);
+                assert!(matches!(*source, GraphError::UndefinedAssetSlot(_)));
+            }
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(g.bindings.len() == 1);
+        assert!(g.get_binding_str("c::input").is_none());
+    }
+
+    #[test]
+    fn add_freestanding() {
+        let mut g = get_example_graph();
+        let start = g.add_freestanding("start", 5u32).expect("redeclared?");
+        assert!(start == "start::value");
+        assert!(matches!(g.add_freestanding("start", 5u32), Err(GraphError::RedeclaredAsset(_))));
+
+        g.bind_asset(&start, "plus_one::one").expect("binding must be doable");
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.run::<u32>("plus_one", "plusone").expect("could not execute") == 6);
+    }
+
+    #[test]
+    fn fallible_body() {
+        let mut g = Graph::new();
+        g.define_freestanding_asset("text", "12".to_string()).expect("redeclared?");
+        g.add_node(create_node!(parse (text : String) -> (number : u32) => {
+                text.parse::<u32>().map_err(SolverError::task)
+            }))
+            .unwrap();
+        g.add_node(create_node!(split (number : u32) -> (half : u32, rest : u32) => {
+                if number > 100 {
+                    return Err(SolverError::NotImplemented);
+                }
+                Ok((number / 2, number % 2))
+            }))
+            .unwrap();
+        g.bind_asset("text", "parse::text").expect("binding must be doable");
+        g.bind_asset("parse::number", "split::number").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.run::<u32>("split", "half").expect("could not execute") == 6);
+            assert!(solver.get_value::<u32>("split::rest").expect("must be u32") == 0);
+        }
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.override_freestanding("text", "twelve".to_string()).expect("declared");
+            match solver.execute("split") {
+                Err(SolverError::InNode { node, source }) => {
+                    assert!(node == "parse");
+                    assert!(matches!(*source, SolverError::Task(_)));
+                }
+                x => panic!("unexpected result {:?}", x),
+            }
+            assert!(solver.get_value::<u32>("parse::number").is_err());
+        }
+    }
+
+    #[test]
+    fn generic_node() {
+        create_node!(identity<T: Clone + 'static> (x : T) -> (y : T) {
+                y = x;
+            });
+        create_node!(pair<A: Clone + 'static, B: Clone + 'static> (a : A, b : B) -> (both : (A, B)) {
+                both = (a, b);
+            });
+
+        let run = |g: &Graph| {
+            let mut cache = ValuesCache::new();
+            let mut solver = GraphSolver::new(g, &mut cache);
+            solver.execute("identity").expect("could not execute");
+            solver.get_values().clone()
+        };
+
+        let mut g = Graph::new();
+        g.define_freestanding_asset("start", 7u32).expect("redeclared?");
+        g.add_node(identity::<u32>()).unwrap();
+        g.bind_asset("start", "identity::x").expect("binding must be doable");
+        assert!(run(&g).get_value::<u32>("identity::y").expect("must be u32") == 7);
+
+        let mut g = Graph::new();
+        g.define_freestanding_asset("start", "text".to_string()).expect("redeclared?");
+        g.add_node(identity::<String>()).unwrap();
+        g.bind_asset("start", "identity::x").expect("binding must be doable");
+        assert!(run(&g).get_value::<String>("identity::y").expect("must be String") == "text");
+
+        let node = pair::<u32, String>();
+        assert!(node.get_asset_type("pair::both") == Some(TypeId::of::<(u32, String)>()));
+    }
+
+    #[test]
+    fn bounded_cache() {
+        let mut cache = BoundedCache::new(2);
+        cache.save_value_str("a", 1u32);
+        cache.save_value_str("b", 2u32);
+        cache.save_value_str("c", 3u32);
+        assert!(cache.len() == 2);
+        assert!(!cache.contains_key("a"));
+        assert!(matches!(cache.get_value::<u32>("a"), Err(SolverError::AssetNotCreated(_))));
+
+        // reading b makes c the least recently used
+        assert!(cache.get_value::<u32>("b").expect("must be u32") == 2);
+        cache.save_value_str("d", 4u32);
+        assert!(cache.contains_key("b"));
+        assert!(!cache.contains_key("c"));
+        assert!(cache.get_value::<u32>("d").expect("must be u32") == 4);
+
+        let mut other = ValuesCache::new();
+        other.save_value_str("b", 20u32);
+        cache.merge_cache(other, MergePolicy::KeepExisting);
+        assert!(cache.get_value::<u32>("b").expect("must be u32") == 2);
+        assert!(cache.len() == 2);
+    }
+
+    #[test]
+    fn cached_assets() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.cached_len() == 0);
+
+        solver.execute("the_one_task").expect("could not execute");
+        let names: Vec<&str> = solver.cached_assets().collect();
+        assert!(names == vec!["gen_one::one", "plus_one::plusone", "the_one_task::last_value"]);
+        assert!(solver.cached_len() == 3);
+    }
+
+    #[test]
+    fn lazy() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        let last = solver.lazy::<f32>("the_one_task::last_value");
+        let one = solver.lazy::<u32>("gen_one::one");
+        let missing = solver.lazy::<u32>("nop::value");
+        assert!(solver.get_values().is_empty());
+
+        assert!(last.get(&mut solver).expect("could not execute") == 3.0);
+        assert!(solver.run_counts().get("gen_one") == Some(&1));
+        assert!(solver.run_counts().get("plus_one") == Some(&1));
+        assert!(one.get(&mut solver).expect("could not execute") == 1);
+        assert!(last.get(&mut solver).expect("could not execute") == 3.0);
+        assert!(solver.run_counts().get("the_one_task") == Some(&1));
+        assert!(matches!(missing.get(&mut solver), Err(SolverError::AssetNotProduced(_))));
+    }
+
+    #[test]
+    fn feedback_input() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(counter () -> (count : u32) feedback (count as previous : u32) {
+                count = previous.unwrap_or(0) + 1;
+            }))
+            .unwrap();
+        assert!(g.find_cycle().is_none());
+
+        let mut cache = ValuesCache::new();
+        for expected in 1..4u32 {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.feedback_input::<u32>("counter::count").unwrap_or(0) == expected - 1);
+            assert!(solver.run::<u32>("counter", "count").expect("could not execute") == expected);
+        }
+    }
+
+    #[test]
+    fn prerequisites() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        assert!(solver.execute_prerequisites("the_one_task").expect("could not execute") == SolverStatus::Executed);
+        assert!(solver.get_value::<u32>("gen_one::one").expect("must be u32") == 1);
+        assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+        assert!(solver.get_value::<f32>("the_one_task::last_value").is_err());
+        assert!(solver.run_counts().get("the_one_task").is_none());
+
+        assert!(solver.execute_prerequisites("gen_one").expect("could not execute") == SolverStatus::Cached);
+        assert!(solver.execute_prerequisites("nop").is_err());
+    }
+
+    #[test]
+    fn progress() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        let mut fractions = Vec::new();
+        solver
+            .execute_with_progress("the_one_task", |fraction| fractions.push(fraction))
+            .expect("could not execute");
+        assert!(fractions.len() == g.cone_size("the_one_task").expect("node exists"));
+        assert!(fractions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(fractions.last() == Some(&1.0));
+        assert!(solver.execute_with_progress("nop", |_| {}).is_err());
+    }
+
+    #[test]
+    fn overlay() {
+        let g = get_example_graph();
+        let mut base = ValuesCache::new();
+        base.save_value_str("gen_one::one", 10u32);
+
+        let mut scratch = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::with_overlay(&g, &base, &mut scratch);
+            solver.execute("the_one_task").expect("could not execute");
+            assert!(solver.get_value::<u32>("gen_one::one").expect("must be u32") == 10);
+            assert!(solver.run_counts().get("gen_one").is_none());
+        }
+        assert!(scratch.get_value::<u32>("plus_one::plusone").expect("must be u32") == 11);
+        assert!(scratch.get_value::<f32>("the_one_task::last_value").expect("must be f32") == 21.0);
+        assert!(scratch.get_value::<u32>("gen_one::one").is_err());
+        assert!(base.len() == 1);
+        assert!(base.get_value::<u32>("gen_one::one").expect("must be u32") == 10);
+    }
+
+    #[test]
+    fn overlay_skips_cached_fast_path() {
+        let g = get_example_graph();
+        let mut scratch = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut scratch);
+            solver.execute("the_one_task").expect("could not execute");
+        }
+
+        // every value is in the scratch cache, yet the base provides a different input
+        let mut base = ValuesCache::new();
+        base.save_value_str("gen_one::one", 10u32);
+        let mut solver = GraphSolver::with_overlay(&g, &base, &mut scratch);
+        assert!(solver.execute("the_one_task").expect("could not execute") == SolverStatus::Executed);
+        assert!(solver.get_value::<f32>("the_one_task::last_value").expect("must be f32") == 21.0);
+    }
+
+    #[test]
+    fn run_counts() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        solver.execute("plus_one").expect("could not execute");
+        solver.execute("the_one_task").expect("could not execute");
+
+        let counts = solver.run_counts();
+        assert!(counts.get("gen_one") == Some(&1));
+        assert!(counts.get("plus_one") == Some(&1));
+        assert!(counts.get("the_one_task") == Some(&1));
+    }
+
+    #[test]
+    fn caching_disabled() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        for _ in 0..2 {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_caching(false);
+            solver.execute("the_one_task").expect("could not execute");
+
+            let counts = solver.run_counts();
+            assert!(counts.len() == 3);
+            assert!(counts.values().all(|count| *count == 1));
+        }
+
+        // with caching enabled, nothing changed since the last solve
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("the_one_task").expect("could not execute");
+        assert!(solver.run_counts().is_empty());
+    }
+
+    #[test]
+    fn replace_node() {
+        let mut g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute("plus_one").expect("could not execute");
+            assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+        }
+
+        g.replace_node(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    plusone = one + 10u32;
+                }
+            )).expect("same signature");
+        assert!(g.get_binding_str("plus_one::one") == Some(&"gen_one::one".to_string()));
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_caching(false);
+            solver.execute("plus_one").expect("could not execute");
+            assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 11);
+        }
+
+        let res = g.replace_node(create_node!(
+                plus_one (one: u32) -> (plusone : f32) {
+                    plusone = one as f32;
+                }
+            ));
+        assert!(matches!(res, Err(GraphError::SignatureMismatch(_))));
+        let res = g.replace_node(create_node!(
+                unknown () -> () { }
+            ));
+        assert!(matches!(res, Err(GraphError::NodeNotFound(_))));
+    }
+
+    #[test]
+    fn cached_fast_path() {
+        use std::cell::Cell;
+        let visits = Rc::new(Cell::new(0));
+        let counter = visits.clone();
+
+        let mut g = get_example_graph();
+        g.add_node(Node::new(
+            "counted".to_string(),
+            move |solver: &mut GraphSolver| {
+                counter.set(counter.get() + 1);
+                let value = solver.get_value::<f32>(solver.input_source("counted::input"))?;
+                if !solver.input_is_new_str(&value, "counted::input")
+                    && solver.use_old_ouput(&["counted::output"])
+                {
+                    return Ok(SolverStatus::Cached);
+                }
+                solver.save_value_str("counted::output", value * 2.0);
+                Ok(SolverStatus::Executed)
+            },
+            vec!["counted::input".to_string()],
+            vec!["counted::output".to_string()],
+        )
+        .cache_aware())
+        .unwrap();
+        g.bind_asset("the_one_task::last_value", "counted::input")
+            .expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute("counted").expect("could not execute") == SolverStatus::Executed);
+        }
+        assert!(visits.get() == 1);
+        {
+            // unchanged re-run, no task is visited
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute("counted").expect("could not execute") == SolverStatus::Cached);
+            assert!(solver.get_value::<f32>("counted::output").expect("must be f32") == 6.0);
+        }
+        assert!(visits.get() == 1);
+        {
+            // all the values are carried forward to the next solve
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute("counted").expect("could not execute");
+        }
+        assert!(visits.get() == 1);
+        {
+            // fed inputs may change the result, tasks are visited
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.feed_input("counted::input", 4f32);
+            solver.execute("counted").expect("could not execute");
+            assert!(solver.get_value::<f32>("counted::output").expect("must be f32") == 8.0);
+        }
+        assert!(visits.get() == 2);
+    }
+
+    #[test]
+    fn cached_fast_path_requires_cache_aware_tasks() {
+        use std::cell::Cell;
+        let ticks = Rc::new(Cell::new(0u32));
+        let clock = ticks.clone();
+
+        let mut g = Graph::new();
+        g.add_node(Node::new(
+            "clock",
+            move |solver: &mut GraphSolver| {
+                clock.set(clock.get() + 1);
+                solver.save_value_str("clock::tick", clock.get());
+                Ok(SolverStatus::Executed)
+            },
+            vec![],
+            vec!["clock::tick".to_string()],
+        ))
+        .unwrap();
+
+        let mut cache = ValuesCache::new();
+        for expected in 1..4u32 {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute("clock").expect("could not execute") == SolverStatus::Executed);
+            assert!(solver.get_value::<u32>("clock::tick").expect("must be u32") == expected);
+        }
+        assert!(ticks.get() == 3);
+    }
+
+    #[test]
+    fn autobind_by_type() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(producer ( ) -> ( v: i32 )
+                                 { v = 1; }))
+            .unwrap();
+        g.add_node(create_node!(consumer ( a : i32, b : f32) -> ()
+                                 { }))
+            .unwrap();
+
+        let bound = g.autobind_by_type().expect("only one candidate");
+        assert!(bound == vec![("producer::v".to_string(), "consumer::a".to_string())]);
+        assert!(g.get_binding_str("consumer::a").expect("must be bound") == "producer::v");
+        assert!(g.get_binding_str("consumer::b").is_none());
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.execute("consumer").is_err());
+    }
+
+    #[test]
+    fn autobind_by_type_ambiguous() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(producer1 ( ) -> ( v: i32 )
+                                 { v = 1; }))
+            .unwrap();
+        g.add_node(create_node!(producer2 ( ) -> ( w: i32 )
+                                 { w = 2; }))
+            .unwrap();
+        g.add_node(create_node!(consumer ( a : i32) -> ()
+                                 { }))
+            .unwrap();
+
+        match g.autobind_by_type() {
+            Err(GraphError::AmbiguousAutobind(name)) => assert!(name == "consumer::a"),
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(g.get_binding_str("consumer::a").is_none());
+    }
+
+    #[test]
+    fn adjacency() {
+        let g = get_example_graph();
+        let (names, m) = g.adjacency();
+        assert!(names == vec!["gen_one", "plus_one", "the_one_task"]);
+
+        let expected = vec![
+            vec![false, true, true],
+            vec![false, false, true],
+            vec![false, false, false],
+        ];
+        assert!(m == expected);
+    }
+
+    #[test]
+    fn duplicate_output() {
+        let mut g = Graph::new();
+        let node = Node::new(
+            "foo",
+            |_solver| Ok(SolverStatus::Executed),
+            vec![],
+            vec!["foo::bar".to_string()],
+        );
+        g.add_node(node).unwrap();
+
+        let node = Node::new(
+            "other",
+            |_solver| Ok(SolverStatus::Executed),
+            vec![],
+            vec!["foo::bar".to_string()],
+        );
+        match g.add_node(node) {
+            Err(GraphError::DuplicateOutput(name)) => assert!(name == "foo::bar"),
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(g.get_node("other").is_none());
+    }
+
+    #[test]
+    fn timeout() {
+        let mut g = Graph::new();
+        for i in 1..5 {
+            let name: String = format!("task{}", i);
+            g.add_node(create_node!(name: name, ( input : u32) -> (output : u32)
+                                     {
+                                         std::thread::sleep(Duration::from_millis(50));
+                                         output = input + 1;
+                                     }))
+                .unwrap();
+        }
+        for i in 1..4 {
+            let src = format!("task{}::output", i);
+            let sink = format!("task{}::input", i + 1);
+            g.bind_asset(src.as_str(), sink.as_str())
+                .expect("binding must be doable");
+        }
+        g.define_freestanding_asset("start", 0u32).expect("could not create asset");
+        g.bind_asset("start", "task1::input")
+            .expect("could not bind first task to start value");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        match solver.execute_with_timeout("task4", Duration::from_millis(75)) {
+            Err(SolverError::Timeout) => {}
+            x => panic!("unexpected result {:?}", x),
+        }
+
+        let sleepers: u32 = solver
+            .run_counts()
+            .iter()
+            .filter(|(name, _)| name.starts_with("task"))
+            .map(|(_, count)| *count)
+            .sum();
+        assert!((1..=2).contains(&sleepers));
+        assert!(solver.get_value::<u32>("task4::output").is_err());
+    }
+
+    #[test]
+    fn assets() {
+        let mut g = get_example_graph();
+        let assets = g.assets();
+        assert!(assets.len() == 6);
+        assert!(assets.iter().filter(|a| a.role == AssetRole::Input).count() == 3);
+        assert!(assets.iter().filter(|a| a.role == AssetRole::Output).count() == 3);
+        assert!(assets.contains(&AssetDescriptor {
+            name: "plus_one::plusone".into(),
+            owner: "plus_one".into(),
+            role: AssetRole::Output,
+        }));
+
+        g.define_freestanding_asset("start", 0u32).expect("could not create asset");
+        let assets = g.assets();
+        assert!(assets.len() == 7);
+        assert!(assets.contains(&AssetDescriptor {
+            name: "start::value".into(),
+            owner: "start".into(),
+            role: AssetRole::Freestanding,
+        }));
+    }
+
+    #[test]
+    fn capture_list() {
+        let table = Rc::new(vec![10u32, 20, 30]);
+
+        let mut g = Graph::new();
+        g.add_node(create_node!(
+                gen_index () -> (index: usize) {
+                    index = 1;
+                }
+            )).unwrap();
+        g.add_node(create_node!(
+                lookup [table: Rc<Vec<u32>>] (index: usize) -> (value: u32) {
+                    value = table[index];
+                }
+            )).unwrap();
+        g.bind_asset("gen_index::index", "lookup::index")
+            .expect("binding must be doable");
+
+        // the task holds its own reference
+        assert!(Rc::strong_count(&table) == 2);
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("lookup").expect("could not execute");
+        assert!(solver.get_value::<u32>("lookup::value").expect("must be u32") == 20);
+    }
+
+    #[test]
+    fn peek() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.peek::<f32>("the_one_task::last_value").is_none());
+            solver.execute("the_one_task").expect("could not execute");
+            assert!(solver.peek::<f32>("the_one_task::last_value") == Some(3f32));
+            assert!(solver.peek::<u32>("the_one_task::last_value").is_none());
+        }
+        {
+            // served from the previous solve, nothing runs
+            let solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.peek::<u32>("plus_one::plusone") == Some(2));
+            assert!(solver.run_counts().is_empty());
+        }
+        {
+            // the current value has another type, the previous one is stale
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.save_value_str("plus_one::plusone", 2.5f32);
+            assert!(solver.peek::<u32>("plus_one::plusone").is_none());
+            assert!(solver.peek::<f32>("plus_one::plusone") == Some(2.5));
+        }
+    }
+
+    #[test]
+    fn changed_inputs() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        cache.save_value_str("gen_one::one", 1u32);
+        cache.save_value_str("plus_one::plusone", 2u32);
+
+        let solver = GraphSolver::new(&g, &mut cache);
+        // inputs are compared against the assets they are bound to
+        let changed = solver.changed_inputs(&[
+            ("plus_one::one", 1u32),
+            ("the_one_task::one", 1u32),
+            ("the_one_task::plusone", 3u32),
+        ]);
+        assert!(changed == vec!["the_one_task::plusone".to_string()]);
+    }
+
+    #[test]
+    fn freestanding_types() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(
+                half (input: f32) -> (output: f32) {
+                    output = input / 2.0;
+                }
+            )).unwrap();
+        g.define_freestanding_asset("int_value", 4u32).unwrap();
+        g.define_freestanding_asset("float_value", 4f32).unwrap();
+
+        match g.bind_asset("int_value", "half::input") {
+            Err(GraphError::TypeMismatch(src, sink)) => {
+                assert!(src == "int_value::value");
+                assert!(sink == "half::input");
+            }
+            _ => panic!("binding must fail"),
+        }
+        assert!(g.get_binding_str("half::input").is_none());
+        g.bind_asset("float_value", "half::input").expect("types match");
+    }
+
+    #[test]
+    fn builder() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .add_node(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    plusone = one + 1u32;
+                }))
+            .bind_asset("gen_one::one", "plus_one::one")
+            .add_node(create_node!(
+                gen_one () ->  (one: u32) {
+                    one = 1u32;
+                }));
+        let g = builder.build().expect("graph should be valid");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("plus_one").expect("could not execute");
+        assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+    }
+
+    #[test]
+    fn builder_chained_bindings() {
+        let build = |bindings: &[(&str, &str)]| {
+            let mut builder = GraphBuilder::new();
+            builder
+                .add_node(create_node!(gen () -> (value : u32) { value = 3; }))
+                .add_node(create_node!(first (x : u32) -> (y : u32) { y = x + 1; }))
+                .add_node(create_node!(second (x : u32) -> (y : u32) { y = x * 2; }));
+            for (src, sink) in bindings {
+                builder.bind_asset(src, sink);
+            }
+            builder.build()
+        };
+
+        let forward = [("gen::value", "first::x"), ("first::x", "second::x")];
+        let backward = [("first::x", "second::x"), ("gen::value", "first::x")];
+        for bindings in &[&forward, &backward] {
+            let g = build(&bindings[..]).expect("graph should be valid");
+            assert!(g.resolve_source("second::x") == Some("gen::value".to_string()));
+
+            let mut cache = ValuesCache::new();
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.run::<u32>("second", "y").expect("could not execute") == 6);
+        }
+
+        // the chain never reaches an output
+        let errors = build(&[("first::x", "second::x")]).err().expect("graph should not be valid");
+        assert!(errors.len() == 1);
+        assert!(matches!(errors[0], GraphError::UndefinedAssetSlot(_)));
+    }
+
+    #[test]
+    fn builder_errors() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .add_node(create_node!(a (x: u32) -> (y : u32) { y = x; }))
+            .add_node(create_node!(b (y: u32) -> (x : u32) { x = y; }))
+            .add_node(create_node!(a () -> () { }))
+            .add_node(create_node!(c () -> (z : f32) { z = 1.0; }))
+            .add_node(create_node!(d (z : u32) -> () { }))
+            .bind_asset("a::y", "b::y")
+            .bind_asset("b::x", "a::x")
+            .bind_asset("c::z", "d::z")
+            .bind_asset("c::nothing", "d::z");
+
+        let errors = match builder.build() {
+            Ok(_) => panic!("graph should not be valid"),
+            Err(errors) => errors,
+        };
+        println!("{:?}", errors);
+        assert!(errors.len() == 4);
+        assert!(errors.iter().any(|e| matches!(e, GraphError::RedefinedNode(_))));
+        assert!(errors.iter().any(|e| matches!(e, GraphError::TypeMismatch(_, _))));
+        assert!(errors.iter().any(|e| matches!(e, GraphError::UndefinedAssetSlot(_))));
+        assert!(errors.iter().any(|e| matches!(e, GraphError::CycleDetected(_))));
+    }
+
+    #[test]
+    fn resolve_source() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(a () -> (out : u32) { out = 7; })).unwrap();
+        g.add_node(create_node!(b (input : u32) -> () { })).unwrap();
+        g.add_node(create_node!(c (input : u32) -> (out : u32) { out = input; })).unwrap();
+
+        g.bind_asset("a::out", "b::input").expect("binding must be doable");
+        g.bind_asset("b::input", "c::input").expect("alias binding must be doable");
+        assert!(g.get_binding_str("c::input").expect("must be bound") == "b::input");
+        assert!(g.resolve_source("c::input") == Some("a::out".to_string()));
+        assert!(g.resolve_source("a::out").is_none());
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("c").expect("could not execute");
+        assert!(solver.get_value::<u32>("c::out").expect("must be u32") == 7);
+    }
+
+    #[test]
+    fn run() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        assert!(solver.run::<f32>("the_one_task", "last_value").expect("could not run") == 3f32);
+        assert!(matches!(
+            solver.run::<f32>("the_one_task", "nothing"),
+            Err(SolverError::AssetNotProduced(_))
+        ));
+        assert!(matches!(
+            solver.run::<f32>("nop", "last_value"),
+            Err(SolverError::NodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn costs() {
+        let mut g = get_example_graph();
+        g.set_cost("the_one_task", 10).expect("node exists");
+        g.set_cost("gen_one", 1).expect("node exists");
+        assert!(g.set_cost("nop", 1).is_err());
+
+        assert!(g.get_cost("the_one_task") == Some(10));
+        assert!(g.get_cost("gen_one") == Some(1));
+        assert!(g.get_cost("plus_one").is_none());
+    }
+
+    #[test]
+    fn description() {
+        let node = create_node!(
+                /// doubles the value,
+                /// rounding down
+                double (x: u32) -> (y: u32) { y = x * 2; }
+            );
+        assert!(node.get_description() == Some("doubles the value, rounding down"));
+
+        let mut g = get_example_graph();
+        g.add_node(node).unwrap();
+        assert!(g.get_node("gen_one").unwrap().get_description().is_none());
+        assert!(g.export_json().contains("\"description\": \"doubles the value, rounding down\""));
+    }
+
+    #[test]
+    fn metadata() {
+        let mut g = get_example_graph();
+        g.set_metadata("plus_one", "group", "math".into())
+            .expect("node exists");
+        g.set_metadata("plus_one", "color", "red".into())
+            .expect("node exists");
+        assert!(g.set_metadata("nop", "group", "math".into()).is_err());
+
+        assert!(g.get_metadata("plus_one", "group") == Some("math"));
+        assert!(g.get_metadata("plus_one", "color") == Some("red"));
+        assert!(g.get_metadata("plus_one", "size").is_none());
+        assert!(g.get_metadata("gen_one", "group").is_none());
+
+        let json = g.export_json();
+        assert!(json.contains("\"metadata\": { \"color\": \"red\", \"group\": \"math\" }"));
+
+        g.remove_node("plus_one").expect("node exists");
+        assert!(g.get_metadata("plus_one", "group").is_none());
+    }
+
+    #[test]
+    fn error_in_node() {
+        let mut g = Graph::new();
+        let node = Node::new(
+            "failing",
+            |solver: &mut GraphSolver| {
+                assert!(solver.current_node() == Some("failing"));
+                solver.get_value::<u32>("missing")?;
+                Ok(SolverStatus::Executed)
+            },
+            vec![],
+            vec![],
+        );
+        g.add_node(node).unwrap();
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        match solver.execute("failing") {
+            Err(SolverError::InNode { node, source }) => {
+                assert!(node == "failing");
+                assert!(matches!(*source, SolverError::AssetNotCreated(_)));
+            }
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(solver.current_node().is_none());
+    }
+
+    #[test]
+    fn partial_results() {
+        let mut g = get_example_graph();
+        g.add_node(create_node!(
+                divide (plusone: u32) -> (result: u32) {
+                    if plusone > 1 {
+                        return Err(SolverError::AssetNotCreated("divide::result".into()));
+                    }
+                    result = 100 / plusone;
+                }
+            )).unwrap();
+        g.bind_asset("plus_one::plusone", "divide::plusone")
+            .expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        match solver.execute("divide") {
+            Err(SolverError::InNode { node, .. }) => assert!(node == "divide"),
+            x => panic!("unexpected result {:?}", x),
+        }
+        assert!(solver.get_values().get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+        assert!(solver.get_values().get_value::<u32>("divide::result").is_err());
+    }
+
+    #[test]
+    fn bind_from_node() {
+        let mut g = Graph::new();
+        for (i, name) in ["first", "second"].iter().enumerate() {
+            g.add_node(create_node!(name: name.to_string(), () -> (value : u32) {
+                value = i as u32;
+            })).unwrap();
+        }
+        g.add_node(create_node!(consumer (a : u32) -> (b : u32)
+                                 { b = a; }))
+            .unwrap();
+
+        assert!(matches!(g.bind_from_node("third", "value", "consumer::a"),
+                         Err(GraphError::NodeNotFound(_))));
+        assert!(matches!(g.bind_from_node("second", "other", "consumer::a"),
+                         Err(GraphError::UndefinedAssetSlot(_))));
+
+        g.bind_from_node("second", "value", "consumer::a").expect("binding must be doable");
+        assert!(g.get_binding_str("consumer::a") == Some(&"second::value".to_string()));
+        g.bind_from_node("first", "first::value", "consumer::a").expect("binding must be doable");
+        assert!(g.get_binding_str("consumer::a") == Some(&"first::value".to_string()));
+        g.bind_from_node("second", "value", "consumer::a").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("consumer").expect("could not execute");
+        assert!(solver.get_value::<u32>("consumer::b").expect("must be u32") == 1);
+    }
+
+    #[test]
+    fn raw_values() {
+        let mut cache = ValuesCache::new();
+        let value: Rc<dyn Any> = Rc::new(vec![1u32, 2, 3]);
+        cache.save_raw("first", value.clone());
+        cache.save_raw("second", value.clone());
+        assert!(Rc::strong_count(&value) == 3);
+        assert!(cache.get_value::<Vec<u32>>("second").expect("must be a vec") == vec![1, 2, 3]);
+
+        let g = Graph::new();
+        let mut last = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut last);
+        let raw = cache.get_raw("first").expect("value exists");
+        solver.save_raw("alias", raw);
+        assert!(Rc::strong_count(&value) == 4);
+        assert!(Rc::ptr_eq(&solver.get_raw("alias").unwrap(), &value));
+        assert!(solver.get_raw("missing").is_none());
+    }
+
+    #[test]
+    fn missing_outputs() {
+        let mut g = get_example_graph();
+        g.add_node(Node::new(
+            "forgetful",
+            |solver: &mut GraphSolver| {
+                let _result = solver.get_value::<u32>(solver.input_source("forgetful::input"))? * 2;
+                Ok(SolverStatus::Executed)
+            },
+            vec!["forgetful::input".to_string()],
+            vec!["forgetful::result".to_string()],
+        ))
+        .unwrap();
+        g.bind_asset("plus_one::plusone", "forgetful::input")
+            .expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.set_check_outputs(true);
+        solver.execute("forgetful").expect("could not execute");
+        assert!(solver.missing_outputs() == ["forgetful::result".to_string()]);
+
+        solver.execute("the_one_task").expect("could not execute");
+        assert!(solver.missing_outputs().len() == 1);
+    }
+
+    #[test]
+    fn alias_asset() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 5u32).expect("redeclared?");
+        g.alias_asset("plus_one::plusone", "sub::two").expect("output exists");
+        g.alias_asset("start", "sub::start").expect("asset exists");
+        assert!(matches!(g.alias_asset("plus_one::nothing", "sub::x"),
+                         Err(GraphError::UndefinedAssetSlot(_))));
+        assert!(matches!(g.alias_asset("plus_one::plusone", "gen_one::one"),
+                         Err(GraphError::RedeclaredAsset(_))));
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(matches!(solver.get_value::<u32>("sub::two"), Err(SolverError::AssetNotCreated(_))));
+        solver.execute("plus_one").expect("could not execute");
+        solver.execute("start").expect("could not execute");
+        assert!(solver.get_value::<u32>("sub::two").expect("must be u32") == 2);
+        assert!(solver.get_value::<u32>("sub::start").expect("must be u32") == 5);
+        assert!(solver.get_values().get("sub::two").is_none());
+    }
+
+    #[test]
+    fn topology_hash() {
+        let g = get_example_graph();
+        assert!(g.topology_hash() == get_example_graph().topology_hash());
+
+        let mut other = get_example_graph();
+        other.set_ordering(NodeOrdering::Insertion);
+        other.set_cost("gen_one", 3).expect("node exists");
+        assert!(g.topology_hash() == other.topology_hash());
+
+        other.add_node(create_node!(extra (x : u32) -> () { println!("{}", x); }))
+            .unwrap();
+        let unbound = other.topology_hash();
+        assert!(g.topology_hash() != unbound);
+        other.bind_asset("gen_one::one", "extra::x").expect("binding must be doable");
+        assert!(other.topology_hash() != unbound);
+    }
+
+    #[test]
+    fn diff() {
+        let mut g = get_example_graph();
+        g.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        assert!(g.diff(&get_example_graph()).removed_freestanding == vec!["start".to_string()]);
+
+        let mut modified = get_example_graph();
+        modified.define_freestanding_asset("start", 1u32).expect("redeclared?");
+        modified.add_node(create_node!(extra (x : u32) -> () { println!("{}", x); }))
+            .unwrap();
+        // rebuild the last task without one of its bindings
+        modified.remove_node("the_one_task").expect("node exists");
+        modified.add_node(create_node!(the_one_task (one: u32, plusone : u32) -> (last_value: f32)
+                                        { last_value = (one + plusone) as f32; }))
+            .unwrap();
+        modified.bind_asset("gen_one::one", "the_one_task::one")
+            .expect("binding must be doable");
+        assert!(g.diff(&g).is_empty());
+
+        let diff = g.diff(&modified);
+        assert!(diff == GraphDiff {
+            added_nodes: vec!["extra".to_string()],
+            removed_bindings: vec![("plus_one::plusone".to_string(), "the_one_task::plusone".to_string())],
+            ..Default::default()
+        });
+
+        let reverse = modified.diff(&g);
+        assert!(reverse.removed_nodes == vec!["extra".to_string()]);
+        assert!(reverse.added_bindings == diff.removed_bindings);
+    }
+
+    #[test]
+    fn side_output() {
+        let mut g = Graph::new();
+        let logger = create_node!(logger |solver| () -> (done : bool) {
+            solver.save_value_str("logger::lines", 3u32);
+            done = true;
+        });
+        g.add_node(logger).unwrap();
+        g.add_node(create_node!(report (lines : u32) -> (text : String)
+                                 { text = format!("{} lines", lines); }))
+            .unwrap();
+
+        assert!(g.bind_asset("logger::lines", "report::lines").is_err());
+        g.declare_side_output("logger", "logger::lines").expect("node exists");
+        assert!(matches!(g.declare_side_output("logger", "logger::lines"),
+                         Err(GraphError::DuplicateOutput(_))));
+        assert!(matches!(g.declare_side_output("nop", "nop::lines"),
+                         Err(GraphError::NodeNotFound(_))));
+        g.bind_asset("logger::lines", "report::lines").expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        let text = solver.run::<String>("report", "text").expect("could not execute");
+        assert!(text == "3 lines");
+    }
+
+    #[test]
+    fn remove_node() {
+        let mut g = get_example_graph();
+        g.set_cost("plus_one", 3).expect("node exists");
+        g.remove_node("plus_one").expect("node exists");
+        assert!(g.remove_node("plus_one").is_err());
+
+        assert!(g.get_node("plus_one").is_none());
+        assert!(g.get_cost("plus_one").is_none());
+        assert!(matches!(g.what_provides("the_one_task::plusone"), AssetProvider::None));
+        assert!(g.get_binding_str("the_one_task::plusone").is_none());
+        assert!(g.get_binding_str("the_one_task::one").is_some());
+        assert!(g.get_unbound_assets() == vec![&"the_one_task::plusone".to_string()]);
+    }
+
+    #[test]
+    fn prune() {
+        let mut g = get_example_graph();
+        // nothing is known to be needed, nothing is removed
+        assert!(matches!(g.prune(), Err(GraphError::NoTerminals)));
+        assert!(g.node_count() == 3);
+
+        g.add_node(create_node!(sink (last_value : f32) -> ()
+                                 { println!("{}", last_value); }))
+            .unwrap();
+        g.bind_asset("the_one_task::last_value", "sink::last_value")
+            .expect("binding must be doable");
+
+        // a branch which does not reach the terminal
+        g.define_freestanding_asset("factor", 2u32).expect("redeclared?");
+        g.add_node(create_node!(debug (a : u32, factor : u32) -> (b : u32)
+                                 { b = a * factor; }))
+            .unwrap();
+        g.bind_asset("gen_one::one", "debug::a").expect("binding must be doable");
+        g.bind_asset("factor", "debug::factor").expect("binding must be doable");
+
+        let removed = g.prune().expect("graph has terminals");
+        assert!(removed == vec!["debug".to_string(), "factor".to_string()]);
+        assert!(g.node_count() == 4);
+        assert!(g.get_freestanding_assets().is_empty());
+        assert!(g.get_binding_str("debug::a").is_none());
+        assert!(g.prune().expect("graph has terminals").is_empty());
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute_terminals().expect("could not execute");
+        assert!(solver.get_value::<f32>("the_one_task::last_value").expect("must be f32") == 3.0);
     }
 
+    #[cfg(feature = "metrics")]
     #[test]
-    fn node_not_ready() {
-        let g = Graph::new();
+    fn clone_count() {
+        let g = get_example_graph();
         let mut cache = ValuesCache::new();
         let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("the_one_task").expect("could not execute");
+        // plus_one reads one input, the_one_task two
+        assert!(solver.clone_count() == 3);
 
-        let node = create_node!(
-            test (i : u32) -> (x: u32) {
-                x = i +1;
-            }
-        );
+        solver.get_value::<f32>("the_one_task::last_value").expect("must be f32");
+        assert!(solver.clone_count() == 4);
+    }
 
-        assert!(node.run(&mut solver).is_err());
+    #[test]
+    fn execute_from_cache_only() {
+        use std::cell::Cell;
+        let runs = Rc::new(Cell::new(0));
+        let counter = runs.clone();
+
+        let mut g = get_example_graph();
+        g.add_node(Node::new(
+            "counted",
+            move |solver: &mut GraphSolver| {
+                counter.set(counter.get() + 1);
+                let value = solver.get_value::<f32>(solver.input_source("counted::input"))?;
+                solver.save_value_str("counted::output", value * 2.0);
+                Ok(SolverStatus::Executed)
+            },
+            vec!["counted::input".to_string()],
+            vec!["counted::output".to_string()],
+        ))
+        .unwrap();
+        g.bind_asset("the_one_task::last_value", "counted::input")
+            .expect("binding must be doable");
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(matches!(solver.execute_from_cache_only("counted"),
+                             Err(SolverError::AssetNotCreated(_))));
+            assert!(solver.get_values().is_empty());
+            solver.execute("counted").expect("could not execute");
+        }
+        assert!(runs.get() == 1);
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute_from_cache_only("counted").expect("cache is warm");
+            assert!(solver.get_value::<f32>("counted::output").expect("must be f32") == 6.0);
+            assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == 2);
+            assert!(solver.run_counts().is_empty());
+        }
+        assert!(runs.get() == 1);
     }
 
     #[test]
-    fn construct_nodes() {
+    fn renamed_outputs() {
         let mut g = Graph::new();
-        g.add_node(create_node!(no_output ( i : u32, j : u32) -> ()
-                                 {
-                                     println!("{} {}", i, j);
-                                 })).unwrap();
-
-        g.add_node(create_node!(no_input  ( ) -> ( x : f32, y : f64)
-                                 {
-                                     x = 1.0f32;
-                                     y = 4.0;
-                                 })).unwrap();
+        g.add_node(create_node!(gen () -> (value as one: u32, other: u32) {
+            value = 1;
+            other = 2;
+        })).unwrap();
+        g.add_node(create_node!(name: "add".to_string(), (a: u32, b: u32) -> (total as sum: u32) {
+            total = a + b;
+        })).unwrap();
+        assert!(g.get_node("gen").unwrap().get_outs() == ["gen::one".to_string(), "gen::other".to_string()]);
+        g.bind_asset("gen::one", "add::a").expect("binding must be doable");
+        g.bind_asset("gen::other", "add::b").expect("binding must be doable");
 
-        g.add_node(create_node!(both_input_and_output ( i : u32, j : u32)
-                                 -> ( x : f32, y : f64)
-                                 {
-                                     x = i as f32;
-                                     y = j as f64;
-                                 })).unwrap();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("add").expect("could not execute");
+        assert!(solver.get_value::<u32>("gen::one").expect("must be u32") == 1);
+        assert!(solver.get_value::<u32>("add::sum").expect("must be u32") == 3);
+        assert!(solver.get_value::<u32>("gen::value").is_err());
     }
 
     #[test]
-    fn solver() {
-        let g = Graph::new();
+    fn take_value() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(buffer () -> (data: Vec<u8>) {
+            data = vec![7u8; 1024];
+        })).unwrap();
+
         let mut cache = ValuesCache::new();
-        let mut s = GraphSolver::new(&g, &mut cache);
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("buffer").expect("could not execute");
 
-        let a: i32 = 1;
-        s.save_value_str("a", a);
+        assert!(matches!(solver.take_value::<String>("buffer::data"),
+                         Err(SolverError::AssetWrongType(_))));
+        let data = solver.take_value::<Vec<u8>>("buffer::data").expect("must be a vec");
+        assert!(data.len() == 1024);
+        assert!(matches!(solver.take_value::<Vec<u8>>("buffer::data"),
+                         Err(SolverError::AssetNotCreated(_))));
+        assert!(matches!(solver.get_value::<Vec<u8>>("buffer::data"),
+                         Err(SolverError::AssetNotCreated(_))));
+    }
 
-        assert!(s.get_value::<i32>("a").is_ok());
-        assert!(s.get_value::<u32>("a").is_err());
-        assert!(s.get_value::<u32>("j").is_err());
+    #[test]
+    fn node_ordering() {
+        use std::cell::RefCell;
+        let log = Rc::new(RefCell::new(Vec::new()));
 
-        println!(" hey");
+        let mut g = Graph::new();
+        for name in &["zeta", "alpha", "mid"] {
+            let log = log.clone();
+            g.add_node(create_node!(name: name.to_string(), () -> () {
+                log.borrow_mut().push(name.to_string());
+            })).unwrap();
+        }
+        assert!(g.get_ordering() == NodeOrdering::Lexicographic);
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_caching(false);
+            solver.execute_terminals().expect("could not execute");
+        }
+        assert!(*log.borrow() == ["alpha", "mid", "zeta"]);
+
+        g.set_ordering(NodeOrdering::Insertion);
+        log.borrow_mut().clear();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.set_caching(false);
+            solver.execute_terminals().expect("could not execute");
+        }
+        assert!(*log.borrow() == ["zeta", "alpha", "mid"]);
+        let names: Vec<&String> = g.iter().map(|(name, _)| name).collect();
+        assert!(names == ["zeta", "alpha", "mid"]);
     }
 
     #[test]
-    fn graph_with_assets() {
+    fn task_errors() {
+        #[derive(Debug)]
+        struct OutOfRange(u32);
+        impl std::fmt::Display for OutOfRange {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{} is out of range", self.0)
+            }
+        }
+        impl std::error::Error for OutOfRange {}
+
         let mut g = Graph::new();
+        g.add_node(create_node!(parse (text: String) -> (value: u32) {
+            value = text.parse::<u32>().map_err(SolverError::task)?;
+        })).unwrap();
+        g.add_node(create_node!(check (value: u32) -> (valid: u32) {
+            if value > 10 {
+                return Err(SolverError::task(OutOfRange(value)));
+            }
+            valid = value;
+        })).unwrap();
+        g.bind_asset("parse::value", "check::value").expect("binding must be doable");
 
-        g.add_node(create_node!(
-                gen_one () ->  (one: u32) {
-                    println!("gen one");
-                    one = 1u32;
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.feed_input("parse::text", "twelve".to_string());
+        match solver.execute("check") {
+            Err(SolverError::InNode { node, source }) => {
+                assert!(node == "parse");
+                match *source {
+                    SolverError::Task(e) => assert!(e.is::<std::num::ParseIntError>()),
+                    x => panic!("unexpected error {:?}", x),
                 }
-            )).unwrap();
+            }
+            x => panic!("unexpected result {:?}", x),
+        }
 
-        g.add_node(create_node!(
-                plus_one (one: u32) -> (plusone : u32) {
-                    println!("plusone");
-                    plusone = one + 1u32;
-                }
-            )).unwrap();
+        drop(solver);
 
-        g.add_node(create_node!(
-                the_one_task  (one: u32, plusone : u32) -> (last_value: f32) {
-                    println!("the one task");
-                    last_value = (one + plusone) as f32;
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.feed_input("parse::text", "12".to_string());
+        match solver.execute("check") {
+            Err(SolverError::InNode { node, source }) => {
+                assert!(node == "check");
+                match *source {
+                    SolverError::Task(e) => assert!(e.to_string() == "12 is out of range"),
+                    x => panic!("unexpected error {:?}", x),
                 }
-            )).unwrap();
+            }
+            x => panic!("unexpected result {:?}", x),
+        }
+    }
 
-        //do connection
-        g.bind_asset("gen_one::one", "plus_one::one")
-            .expect("binding must be doable");
-        g.bind_asset("plus_one::plusone", "the_one_task::plusone")
-            .expect("binding must be doable");
-        g.bind_asset("gen_one::one", "the_one_task::one")
-            .expect("binding must be doable");
+    #[test]
+    fn freestanding_execution() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(add ( a : u32, b : u32) -> (sum : u32)
+                                 { sum = a + b; }))
+            .unwrap();
+        g.define_freestanding_asset("first", 40u32).expect("redeclared?");
+        g.define_freestanding_asset("second", 2u32).expect("redeclared?");
+        assert!(g.define_freestanding_asset("second", 2u32).is_err());
 
-        g.get_binding_str("plus_one::one")
-            .expect("binding must be set");
-        g.get_binding_str("the_one_task::plusone")
-            .expect("binding must be set");
-        g.get_binding_str("the_one_task::one")
-            .expect("binding must be set");
+        g.bind_asset("first", "add::a").expect("binding must be doable");
+        g.bind_asset("second", "add::b").expect("binding must be doable");
+        assert!(g.get_binding_str("add::a").expect("must be bound") == "first::value");
 
         let mut cache = ValuesCache::new();
-
-        for _ in 0..10 {
+        {
             let mut solver = GraphSolver::new(&g, &mut cache);
-            assert!(solver.execute("nop").is_err());
-            solver.execute("the_one_task").expect("could not execute");
-            solver
-                .get_value::<f32>("the_one_task::last_value")
-                .expect("could not retrieve result");
+            solver.execute("add").expect("could not execute");
+            assert!(solver.get_value::<u32>("add::sum").expect("must be u32") == 42);
         }
-
-        assert!(
-            cache
-                .get_value::<f32>("the_one_task::last_value")
-                .expect("must be f32") == 3f32
-        );
+        assert!(cache.get_value::<u32>("first::value").expect("must be u32") == 40);
     }
 
     #[test]
-    fn terminals() {
+    fn disabled_nodes() {
         let mut g = Graph::new();
-        g.add_node(create_node!(sink_1 ( input : u32) -> ()
+        g.add_node(create_node!(no_input () -> ( o : u32)
                                  {
-                                     println!("sink 1 {}", input);
+                                     o =  1234;
                                  })).unwrap();
-
-        g.add_node(create_node!(sink_2 ( name : u32) -> ()
+        g.add_node(create_node!(sink_1 ( input : u32) -> ()
                                  {
-                                     println!("sink 2 {}", name);
+                                     println!("sink 1 {}", input);
                                  })).unwrap();
-
-        g.add_node(create_node!(no_input () -> ( o : u32)
+        g.add_node(create_node!(sink_2 ( input : u32) -> ()
                                  {
-                                     o =  1234;
-                                     println!("produce {}", o);
+                                     println!("sink 2 {}", input);
                                  })).unwrap();
-
         g.bind_asset("no_input::o", "sink_1::input")
             .expect("binding must be doable");
-        g.bind_asset("no_input::o", "sink_2::name")
+        g.bind_asset("no_input::o", "sink_2::input")
             .expect("binding must be doable");
 
-        // slices have no size...
-        // assert!(g.get_terminals().size() == 1);
+        g.set_enabled("sink_2", false).expect("node exists");
+        assert!(!g.is_enabled("sink_2"));
+        assert!(g.set_enabled("nop", false).is_err());
 
         let mut cache = ValuesCache::new();
-        {
-            let mut solver = GraphSolver::new(&g, &mut cache);
-            solver.execute_terminals().expect("this should run");
-        }
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        let report = solver.execute_terminals_partial().expect("this should run");
+        assert!(report.executed.contains(&("sink_1".to_string(), SolverStatus::Executed)));
+        assert!(report.executed.contains(&("sink_2".to_string(), SolverStatus::Skipped)));
+        assert!(solver.run_counts().get("sink_2").is_none());
+    }
 
-        assert!(cache.get_value::<u32>("no_input::o").expect("must be f32") == 1234);
+    #[test]
+    fn active_freestanding() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(node1 ( a : u32) -> ()
+                                 { }))
+            .unwrap();
+        g.define_freestanding_asset("used", 0u32).expect("redeclared?");
+        g.define_freestanding_asset("unused", 1u32).expect("redeclared?");
+        assert!(g.active_freestanding().is_empty());
+
+        g.bind_asset("used", "node1::a").expect("binding must be doable");
+        assert!(g.active_freestanding() == vec!["used"]);
     }
 
     #[test]
-    fn freestanding_assets() {
+    fn feed_input() {
         let mut g = Graph::new();
+        g.add_node(create_node!(double ( a : u32) -> (b : u32)
+                                 { b = a * 2; }))
+            .unwrap();
 
-        g.add_node(create_node!(node1 ( a : u32) -> ()
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        assert!(solver.execute("double").is_err());
+
+        solver.feed_input("double::a", 21u32);
+        solver.execute("double").expect("input was fed");
+        assert!(solver.get_value::<u32>("double::b").expect("must be u32") == 42);
+    }
+
+    #[test]
+    fn counts() {
+        let mut g = Graph::new();
+        assert!(g.is_empty());
+        assert!(g.node_count() == 0);
+        assert!(g.terminal_count() == 0);
+
+        g.add_node(create_node!(producer ( ) -> ( v: i32 )
+                                 { v = 1; }))
+            .unwrap();
+        g.add_node(create_node!(consumer ( a : i32) -> ()
                                  { }))
             .unwrap();
-        assert!(g.get_unbound_assets().len() == 1);
+        g.define_freestanding_asset("start", 0u32).expect("redeclared?");
+        assert!(!g.is_empty());
+        assert!(g.node_count() == 3);
+        assert!(g.terminal_count() == 1);
+    }
 
-        g.define_freestanding_asset("startvalue", 0).expect("redeclared?");
+    #[test]
+    fn conditional_bindings() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(a () -> (out : u32) { out = 1; })).unwrap();
+        g.add_node(create_node!(b () -> (out : u32) { out = 2; })).unwrap();
+        g.add_node(create_node!(switch (on : bool) -> (flag : bool) { flag = on; })).unwrap();
+        g.add_node(create_node!(c (x : u32) -> (y : u32) { y = x; })).unwrap();
 
-        g.bind_asset("startvalue", "node1::a")
+        g.bind_asset("b::out", "c::x").expect("binding must be doable");
+        g.bind_conditional("a::out", "c::x", "switch::flag")
             .expect("binding must be doable");
+        assert!(g.bind_conditional("a::out", "c::x", "b::out").is_err());
+        assert!(g.bind_conditional("a::out", "c::x", "nop::flag").is_err());
 
-        println!("{:?}", g.get_freestanding_assets());
-        println!("{:?}", g.get_unbound_assets());
-        assert!(g.get_unbound_assets().len() == 0);
+        let mut cache = ValuesCache::new();
+        for &on in &[true, false, true] {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.feed_input("switch::on", on);
+            solver.execute("c").expect("could not execute");
+
+            let expected = if on { 1 } else { 2 };
+            assert!(solver.get_value::<u32>("c::y").expect("must be u32") == expected);
+        }
+
+        // flags are computed by tasks like any other, cancellation stops them as well
+        let cancel = AtomicBool::new(true);
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.feed_input("switch::on", false);
+        assert!(matches!(solver.execute_cancellable("c", &cancel), Err(SolverError::Cancelled)));
+        assert!(solver.get_value::<bool>("switch::flag").is_err());
+        assert!(solver.run_counts().is_empty());
     }
 
     #[test]
-    fn unbound_assets() {
+    fn execute_from_cache_only_conditional() {
         let mut g = Graph::new();
-        assert!(g.get_unbound_assets().len() == 0);
+        g.add_node(create_node!(a () -> (out : u32) { out = 1; })).unwrap();
+        g.add_node(create_node!(b () -> (out : u32) { out = 2; })).unwrap();
+        g.add_node(create_node!(switch (on : bool) -> (flag : bool) { flag = on; })).unwrap();
+        g.add_node(create_node!(c (x : u32) -> (y : u32) { y = x; })).unwrap();
+        g.bind_asset("b::out", "c::x").expect("binding must be doable");
+        g.bind_conditional("a::out", "c::x", "switch::flag")
+            .expect("binding must be doable");
 
-        g.add_node(create_node!(consumer ( a : u32, b: i32, c: f32) -> ()
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.feed_input("switch::on", true);
+            solver.execute("c").expect("could not execute");
+            assert!(solver.get_value::<u32>("b::out").is_err());
+        }
+        {
+            // the branch not taken is not cached, nor needed
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            solver.execute_from_cache_only("c").expect("cache is warm");
+            assert!(solver.get_value::<u32>("c::y").expect("must be u32") == 1);
+            assert!(solver.get_value::<bool>("switch::flag").expect("must be bool"));
+            assert!(solver.run_counts().is_empty());
+        }
+    }
+
+    #[test]
+    fn consume_all_outputs() {
+        let mut g = Graph::new();
+        g.add_node(create_node!(producer () -> (a : u32, b : f32) {
+            a = 1;
+            b = 2.0;
+        })).unwrap();
+        g.add_node(create_node!(aggregator (from producer) -> (count : usize, sum : f32) {
+            count = producer.len();
+            sum = producer.get_value::<u32>("producer::a")? as f32
+                + producer.get_value::<f32>("producer::b")?;
+        })).unwrap();
+
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("aggregator").expect("could not execute");
+        assert!(solver.get_value::<usize>("aggregator::count").expect("must be usize") == 2);
+        assert!(solver.get_value::<f32>("aggregator::sum").expect("must be f32") == 3.0);
+    }
+
+    #[test]
+    fn validate_types() {
+        let mut g = get_example_graph();
+        assert!(g.validate_types().is_ok());
+
+        g.add_node(create_node!(consumer ( a : f32, b : String, c : u32) -> ()
                                  { }))
             .unwrap();
-        assert!(g.get_unbound_assets().len() == 3);
+        g.bind_asset("gen_one::one", "consumer::a").expect("binding must be doable");
+        g.bind_asset("plus_one::plusone", "consumer::b").expect("binding must be doable");
+        g.bind_asset("plus_one::plusone", "consumer::c").expect("binding must be doable");
 
-        g.add_node(create_node!(producer ( ) -> ( v: i32 )
-                                 { v = 1; }))
-            .unwrap();
-        assert!(g.get_unbound_assets().len() == 3);
+        let errors = g.validate_types().expect_err("types do not match");
+        assert!(errors.len() == 2);
+        assert!(errors.iter().any(|e| matches!(e,
+            GraphError::TypeMismatch(src, sink) if src == "gen_one::one" && sink == "consumer::a")));
+        assert!(errors.iter().any(|e| matches!(e,
+            GraphError::TypeMismatch(src, sink) if src == "plus_one::plusone" && sink == "consumer::b")));
+    }
 
-        g.bind_asset("producer::v", "consumer::b")
-            .expect("binding must be doable");
-        println!("{:?}", g.get_unbound_assets());
-        assert!(g.get_unbound_assets().len() == 2);
+    #[test]
+    fn step_iter() {
+        let g = get_example_graph();
+        let mut cache = ValuesCache::new();
+        let mut solver = GraphSolver::new(&g, &mut cache);
+
+        let mut steps = Vec::new();
+        for step in solver.step_iter("the_one_task") {
+            let (name, status) = step.expect("step should run");
+            steps.push(name);
+            assert!(status == SolverStatus::Executed);
+        }
+        assert!(steps == vec!["gen_one", "plus_one", "the_one_task"]);
+        assert!(solver.get_value::<f32>("the_one_task::last_value").expect("must be f32") == 3f32);
+
+        let mut failing = solver.step_iter("nop");
+        assert!(matches!(failing.next(), Some(Err(SolverError::NodeNotFound(_)))));
+        assert!(failing.next().is_none());
+    }
+
+    #[test]
+    fn retry() {
+        use std::cell::Cell;
+
+        let attempts = Rc::new(Cell::new(0u32));
+        let mut g = Graph::new();
+        g.add_node(create_node!(
+                flaky [attempts: Rc<Cell<u32>>] () -> (out: u32) {
+                    attempts.set(attempts.get() + 1);
+                    if attempts.get() < 3 {
+                        return Err(SolverError::AssetNotCreated("flaky::out".into()));
+                    }
+                    out = 1;
+                }
+            )).unwrap();
+
+        let mut cache = ValuesCache::new();
+        {
+            let mut solver = GraphSolver::new(&g, &mut cache);
+            assert!(solver.execute("flaky").is_err());
+        }
+
+        attempts.set(0);
+        g.set_retry("flaky", 3).expect("node exists");
+        let mut solver = GraphSolver::new(&g, &mut cache);
+        solver.execute("flaky").expect("should succeed on the third attempt");
+        assert!(attempts.get() == 3);
+        assert!(solver.get_value::<u32>("flaky::out").expect("must be u32") == 1);
+    }
+
+    #[test]
+    fn shared_nodes() {
+        let shared: Rc<dyn NodeRunner> = Rc::new(create_node!(
+                plus_one (one: u32) -> (plusone : u32) {
+                    plusone = one + 1u32;
+                }
+            ));
+
+        let mut graphs = Vec::new();
+        for i in 0..2u32 {
+            let mut g = Graph::new();
+            g.add_node(create_node!(name: format!("gen{}", i), () -> (one : u32) {
+                one = i;
+            })).unwrap();
+            g.add_shared_node(shared.clone()).unwrap();
+            g.bind_asset(&format!("gen{}::one", i), "plus_one::one")
+                .expect("binding must be doable");
+            graphs.push(g);
+        }
+        assert!(Rc::strong_count(&shared) == 3);
+
+        for (i, g) in graphs.iter().enumerate() {
+            let mut cache = ValuesCache::new();
+            let mut solver = GraphSolver::new(g, &mut cache);
+            solver.execute("plus_one").expect("could not execute");
+            assert!(solver.get_value::<u32>("plus_one::plusone").expect("must be u32") == i as u32 + 1);
+        }
     }
 
     // use test::Bencher;
@@ -18,36 +18,212 @@ macro_rules! asset_string(
     };
 );
 
+/// asset name of an output, the local variable name unless renamed with `as`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! output_str(
+    ($node:ident, $out:ident) => {
+        asset_str!($node, $out)
+    };
+    ($node:ident, $out:ident as $asset:ident) => {
+        asset_str!($node, $asset)
+    };
+);
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! output_string(
+    ($node:expr, $out:ident) => {
+        asset_string!(as_str, $node, $out)
+    };
+    ($node:expr, $out:ident as $asset:ident) => {
+        asset_string!(as_str, $node, $asset)
+    };
+);
+
 /// Macro to generate a Node (Task).
 /// It requires:
 ///   a name (as used in the solver to execute it),
 ///   a set of inputs,
 ///   a set of outputs, and
 ///   a set of statements which are the body of the task
+///
+/// Optionally, a capture list between brackets can follow the name. Each of the listed
+/// variables is cloned once into the task, which is handy to share read-only state
+/// (i.e. an `Rc`) among tasks:
+///
+/// ```
+/// use rgraph::*;
+/// use std::rc::Rc;
+///
+/// let factor = Rc::new(2u32);
+/// create_node!(
+///          scale [factor: Rc<u32>] (a: u32) -> (b: u32) {
+///              b = a * *factor;
+///          }
+///      );
+/// ```
+///
+/// An output can be saved under an asset name different from the local variable, using
+/// `variable as asset`:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          half (value: f32) -> (result as half: f32) {
+///              result = value / 2.0;
+///          }
+///      );
+/// ```
+///
+/// A task can also consume every output of other tasks, declaring `from producer` as
+/// inputs. Each producer is then available in the body as a `ValuesCache` keyed by the
+/// output asset names:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          log (from task1, from task2) -> () {
+///              println!("{:?} {:?}", task1.keys(), task2.keys());
+///          }
+///      );
+/// ```
+///
+/// The body can also be an expression, after `=>`, evaluating to a
+/// `Result<(outputs), SolverError>`: a tuple with the outputs in declaration order (a
+/// plain value for a single output). Errors can then be propagated with `?`, other error
+/// types are wrapped with `SolverError::task`:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          parse (text: String) -> (number: u32, twice: u32) => {
+///              let number: u32 = text.parse().map_err(SolverError::task)?;
+///              Ok((number, number * 2))
+///          }
+///      );
+/// ```
+///
+/// A task can read its own outputs as computed in the previous solve, listing them after
+/// the outputs with `feedback (output as variable: Type)`. Each variable is an `Option`,
+/// `None` on the first solve. Such tasks are never served from the cache:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          counter () -> (count: u32) feedback (count as previous: u32) {
+///              count = previous.unwrap_or(0) + 1;
+///          }
+///      );
+/// ```
+///
+/// The solver running the task is hidden from the body. Naming it between bars after the
+/// task name makes it available, i.e. to report warnings (see `GraphSolver::warn`) or to
+/// save side outputs. Every form above accepts it, as well as generic tasks:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          clamp |solver| (value: i32) -> (clamped: u32) {
+///              if value < 0 {
+///                  solver.warn(format!("negative value {} clamped to 0", value));
+///              }
+///              clamped = value.max(0) as u32;
+///          }
+///      );
+/// ```
+///
+/// Generic tasks declare their type parameters after the name. Instead of a node, a
+/// function with the same name is defined, which creates the node for the given types.
+/// Bounds are single identifiers or lifetimes (i.e. `T: Clone + 'static`), parameters
+/// must be comparable (see `Comparable`) since inputs are checked against the cache:
+///
+/// ```
+/// use rgraph::*;
+///
+/// create_node!(
+///          identity<T: Clone + 'static> (x: T) -> (y: T) {
+///              y = x;
+///          }
+///      );
+///
+/// let mut g = Graph::new();
+/// g.add_node(identity::<u32>()).expect("first node");
+/// ```
+///
+/// Outputs are saved in the cache, so their types must be `'static`. Borrowing outputs
+/// are rejected at the declaration of the task (see `output_must_be_static`):
+///
+/// ```compile_fail
+/// use rgraph::*;
+///
+/// fn borrowing<'a, T: Clone>(value: &'a T) {
+///     create_node!(
+///              first () -> (item: &'a T) {
+///                  item = value;
+///              }
+///          );
+/// }
+/// ```
+///
+/// Doc comments before the name become the description of the task (see
+/// `Node::with_description`), lines are joined with spaces:
+///
+/// ```
+/// use rgraph::*;
+///
+/// let node = create_node!(
+///          /// halves the value
+///          half (value: f32) -> (result: f32) {
+///              result = value / 2.0;
+///          }
+///      );
+/// assert!(node.get_description() == Some("halves the value"));
+/// ```
 #[macro_export]
 macro_rules! create_node(
 
+    // doc comments describe the task, lines are gathered one by one
+    ( @doc [ $( $lines:literal )+ ] #[doc = $doc:literal] $( $rest:tt )+ ) => {
+        create_node!( @doc [ $( $lines )+ $doc ] $( $rest )+ )
+    };
+    ( @doc [ $( $lines:literal )+ ] $( $rest:tt )+ ) => {
+        create_node!( $( $rest )+ ).with_description(
+            [ $( $lines.trim() ),+ ].join(" ")
+        )
+    };
+    ( #[doc = $doc:literal] $( $rest:tt )+ ) => {
+        create_node!( @doc [ $doc ] $( $rest )+ )
+    };
+
     // name as expression allows to generate function names programatically
     ( name: $name:expr,
       ( $( $in:ident : $it:ty ),* ) ->
-      ( $( $out:ident : $ot:ty ),* ) $( $body:stmt )+ ) => {
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {
         {
+            // unused by tasks without inputs nor outputs
+            #[allow(unused_variables)]
             let tmp = $name.clone();
+            $( output_must_be_static::<$ot>(); )*
             Node::new($name.clone(),
                move | solver : &mut GraphSolver  |
                {
                     // get inputs
                     $(
                         let $in : $it = solver.get_value::<$it>(
-                                            solver.get_binding(&asset_string!(as_str, tmp, $in))?
+                                            solver.input_source(&asset_string!(as_str, tmp, $in))
                                     )?;
                     )*
 
                     // if any of the inputs is new (or there are no imputs)
                     let eq = [ $( solver.input_is_new(&$in, &asset_string!(as_str, tmp, $in)) ),* ];
-                    if !eq.iter().fold(false, |acum, b| acum || *b){
-                        let tmp = tmp.clone();
-                        let outs = vec!( $( asset_string!(as_str, tmp, $out) ),* );
+                    if solver.caching_enabled() && !eq.iter().fold(false, |acum, b| acum || *b){
+                        let outs : Vec<String> = vec!( $( output_string!(tmp, $out $( as $asset )?) ),* );
                         if solver.use_old_ouput(&outs){
                             return Ok(SolverStatus::Cached);
                         }
@@ -59,54 +235,253 @@ macro_rules! create_node(
 
                     // save outputs (re assign, this guarantees output type)
                     $( let $out : $ot = $out; )*
-                    $( solver.save_value(&asset_string!(as_str, tmp, $out), $out); )*
+                    $( solver.save_value(&output_string!(tmp, $out $( as $asset )?), $out); )*
 
                     // set the status to executed
                     Ok(SolverStatus::Executed)
                },
                vec!( $( asset_string!(as_str, $name.clone(), $in) ),* ),
-               vec!( $( asset_string!(as_str, $name.clone(), $out) ),* ),
-           )
+               vec!( $( output_string!($name.clone(), $out $( as $asset )?) ),* ),
+           ).with_types(
+               vec!( $( std::any::TypeId::of::<$it>() ),* ),
+               vec!( $( std::any::TypeId::of::<$ot>() ),* ),
+           ).cache_aware()
         }
     };
 
-    // no quotes in name, more function like
+    // consumes all the outputs of other tasks, each one is read as a ValuesCache
+    // named after the producer task
     ( $name:ident
+      ( $( from $src:ident ),+ ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {
+        create_node!( $name |solver| ( $( from $src ),+ ) ->
+                      ( $( $out $( as $asset )? : $ot ),* ) $( $body )+ )
+    };
+    ( $name:ident | $solver:ident |
+      ( $( from $src:ident ),+ ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {{
+        $( output_must_be_static::<$ot>(); )*
+        Node::new(stringify!($name).to_string(),
+           move | $solver : &mut GraphSolver  |
+           {
+                // get all outputs of each producer
+                $( let $src : ValuesCache = $solver.outputs_of(stringify!($src))?; )+
+
+                // exec body (declare out vars, uninitalized)
+                $( let $out : $ot; )*
+                $( $body )+
+
+                // save outputs (re assign, this guarantees output type)
+                $( let $out : $ot = $out; )*
+                $( $solver.save_value_str(output_str!($name, $out $( as $asset )?), $out); )*
+
+                // set the status to executed
+                Ok(SolverStatus::Executed)
+           },
+           vec!(),
+           vec!( $( output_str!($name, $out $( as $asset )?).to_string() ),* ),
+       ).with_types(
+           vec!(),
+           vec!( $( std::any::TypeId::of::<$ot>() ),* ),
+       ).with_producers(
+           vec!( $( stringify!($src).to_string() ),+ ),
+       )
+    }};
+
+    // fallible body, an expression evaluating to a Result with the outputs
+    ( $name:ident
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) => $body:expr ) => {
+        create_node!( $name |solver| ( $( $in : $it ),* ) ->
+                      ( $( $out $( as $asset )? : $ot ),* ) => $body )
+    };
+    ( $name:ident | $solver:ident |
       ( $( $in:ident : $it:ty ),* ) ->
-      ( $( $out:ident : $ot:ty ),* ) $( $body:stmt )+ ) => {
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) => $body:expr ) => {{
+        $( output_must_be_static::<$ot>(); )*
         Node::new(stringify!($name).to_string(),
-           move | solver : &mut GraphSolver  |
+           move | $solver : &mut GraphSolver  |
            {
                 // get inputs
                 $(
-                    let $in : $it = solver.get_value::<$it>(
-                                        solver.get_binding(asset_str!($name,$in))?
+                    let $in : $it = $solver.get_value::<$it>(
+                                        $solver.input_source(asset_str!($name,$in))
                                 )?;
                 )*
 
                 // if any of the inputs is new (or there are no imputs)
-                let eq = [ $( solver.input_is_new_str(&$in, asset_str!($name,$in)) ),* ];
-                if !eq.iter().fold(false, |acum, b| acum || *b){
-                    let outs : Vec<&'static str> = vec!( $( asset_str!($name,$out) ),* );
-                    if solver.use_old_ouput(&outs){
+                let eq = [ $( $solver.input_is_new_str(&$in, asset_str!($name,$in)) ),* ];
+                if $solver.caching_enabled() && !eq.iter().fold(false, |acum, b| acum || *b){
+                    let outs : Vec<&'static str> = vec!( $( output_str!($name, $out $( as $asset )?) ),* );
+                    if $solver.use_old_ouput(&outs){
                         return Ok(SolverStatus::Cached);
                     }
                 }
 
+                // exec body, errors are returned as they are
+                #[allow(unused_parens)]
+                let result : Result<( $( $ot ),* ), SolverError> = $body;
+                #[allow(unused_parens)]
+                let ( $( $out ),* ) = result?;
+
+                // save outputs
+                $( $solver.save_value_str(output_str!($name, $out $( as $asset )?), $out); )*
+
+                // set the status to executed
+                Ok(SolverStatus::Executed)
+           },
+           vec!( $( asset_str!($name, $in).to_string() ),* ),
+           vec!( $( output_str!($name, $out $( as $asset )?).to_string() ),* ),
+       ).with_types(
+           vec!( $( std::any::TypeId::of::<$it>() ),* ),
+           vec!( $( std::any::TypeId::of::<$ot>() ),* ),
+       ).cache_aware()
+    }};
+
+    // reads outputs of this same task from the previous solve, the task always runs
+    ( $name:ident
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* )
+      feedback ( $( $src:ident as $fb:ident : $ft:ty ),+ ) $( $body:stmt )+ ) => {
+        create_node!( $name |solver| ( $( $in : $it ),* ) ->
+                      ( $( $out $( as $asset )? : $ot ),* )
+                      feedback ( $( $src as $fb : $ft ),+ ) $( $body )+ )
+    };
+    ( $name:ident | $solver:ident |
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* )
+      feedback ( $( $src:ident as $fb:ident : $ft:ty ),+ ) $( $body:stmt )+ ) => {{
+        $( output_must_be_static::<$ot>(); )*
+        Node::new(stringify!($name).to_string(),
+           move | $solver : &mut GraphSolver  |
+           {
+                // get inputs
+                $(
+                    let $in : $it = $solver.get_value::<$it>(
+                                        $solver.input_source(asset_str!($name,$in))
+                                )?;
+                )*
+                $( let $fb : Option<$ft> = $solver.feedback_input::<$ft>(asset_str!($name, $src)); )+
+
                 // exec body (declare out vars, uninitalized)
                 $( let $out : $ot; )*
                 $( $body )+
 
                 // save outputs (re assign, this guarantees output type)
                 $( let $out : $ot = $out; )*
-                $( solver.save_value_str(asset_str!($name,$out), $out); )*
+                $( $solver.save_value_str(output_str!($name, $out $( as $asset )?), $out); )*
 
                 // set the status to executed
                 Ok(SolverStatus::Executed)
            },
            vec!( $( asset_str!($name, $in).to_string() ),* ),
-           vec!( $( asset_str!($name, $out).to_string() ),* ),
+           vec!( $( output_str!($name, $out $( as $asset )?).to_string() ),* ),
+       ).with_types(
+           vec!( $( std::any::TypeId::of::<$it>() ),* ),
+           vec!( $( std::any::TypeId::of::<$ot>() ),* ),
+       ).with_feedback(
+           vec!( $( asset_str!($name, $src).to_string() ),+ ),
        )
+    }};
+
+    // generic task, defines a function creating the node for the given type parameters
+    ( $name:ident < $( $gp:ident $( : $bound:tt $( + $more:tt )* )? ),+ > $( | $solver:ident | )?
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {
+        fn $name< $( $gp $( : $bound $( + $more )* )? ),+ >()
+            -> Node<impl Fn(&mut GraphSolver) -> Result<SolverStatus, SolverError>>
+        where
+            $( $gp: Comparable ),+
+        {
+            create_node!( $name $( | $solver | )? ( $( $in : $it ),* ) -> ( $( $out $( as $asset )? : $ot ),* ) $( $body )+ )
+        }
+    };
+
+    // no quotes in name, more function like
+    ( $name:ident
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {
+        create_node!( $name |solver| ( $( $in : $it ),* ) ->
+                      ( $( $out $( as $asset )? : $ot ),* ) $( $body )+ )
+    };
+    ( $name:ident | $solver:ident |
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {{
+        $( output_must_be_static::<$ot>(); )*
+        Node::new(stringify!($name).to_string(),
+           move | $solver : &mut GraphSolver  |
+           {
+                // get inputs
+                $(
+                    let $in : $it = $solver.get_value::<$it>(
+                                        $solver.input_source(asset_str!($name,$in))
+                                )?;
+                )*
+
+                // if any of the inputs is new (or there are no imputs)
+                let eq = [ $( $solver.input_is_new_str(&$in, asset_str!($name,$in)) ),* ];
+                if $solver.caching_enabled() && !eq.iter().fold(false, |acum, b| acum || *b){
+                    let outs : Vec<&'static str> = vec!( $( output_str!($name, $out $( as $asset )?) ),* );
+                    if $solver.use_old_ouput(&outs){
+                        return Ok(SolverStatus::Cached);
+                    }
+                }
+
+                // exec body (declare out vars, uninitalized)
+                $( let $out : $ot; )*
+                $( $body )+
+
+                // save outputs (re assign, this guarantees output type)
+                $( let $out : $ot = $out; )*
+                $( $solver.save_value_str(output_str!($name, $out $( as $asset )?), $out); )*
+
+                // set the status to executed
+                Ok(SolverStatus::Executed)
+           },
+           vec!( $( asset_str!($name, $in).to_string() ),* ),
+           vec!( $( output_str!($name, $out $( as $asset )?).to_string() ),* ),
+       ).with_types(
+           vec!( $( std::any::TypeId::of::<$it>() ),* ),
+           vec!( $( std::any::TypeId::of::<$ot>() ),* ),
+       ).cache_aware()
+    }};
+
+    // capture list, each element is cloned once into the task scope
+    ( $name:ident [ $( $cap:ident : $ct:ty ),* ] $( | $solver:ident | )?
+      ( $( $in:ident : $it:ty ),* ) ->
+      ( $( $out:ident $( as $asset:ident )? : $ot:ty ),* ) $( $body:stmt )+ ) => {
+        {
+            $( let $cap : $ct = $cap.clone(); )*
+            create_node!( $name $( | $solver | )? ( $( $in : $it ),* ) -> ( $( $out $( as $asset )? : $ot ),* ) $( $body )+ )
+        }
+    };
+);
+
+/// Macro to generate a linear sequence of tasks, the output of each task is bound to the
+/// input of the next one. Every task has a single output and, but for the first one, a
+/// single input. Tasks are declared as in `create_node!`, separated by commas, and added
+/// to the graph given as first argument (see `Graph::add_pipeline`):
+///
+/// ```
+/// use rgraph::*;
+///
+/// let mut g = Graph::new();
+/// pipeline!(&mut g,
+///     gen () -> (value: u32) { value = 1; },
+///     double (value: u32) -> (result: u32) { result = value * 2; }
+/// ).expect("pipeline must be valid");
+/// ```
+#[macro_export]
+macro_rules! pipeline(
+    ( $graph:expr,
+      $( $name:ident ( $( $in:ident : $it:ty )? ) ->
+                     ( $out:ident : $ot:ty ) $body:block ),+ $(,)? ) => {
+        Graph::add_pipeline($graph, vec!(
+            $(
+                Box::new(create_node!( $name ( $( $in : $it )? ) -> ( $out : $ot ) $body ))
+                    as Box<dyn NodeRunner>
+            ),+
+        ))
     };
 );
 
@@ -118,5 +493,7 @@ mod tests {
         assert!(asset_str!(one, two) == "one::two");
         assert!(asset_str!("one", two) == "one::two");
         assert!(asset_str!("one", "two") == "one::two");
+        assert!(output_str!(one, two) == "one::two");
+        assert!(output_str!(one, two as three) == "one::three");
     }
 }
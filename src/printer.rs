@@ -13,16 +13,52 @@ pub fn print_info(graph: &Graph) {
         for output in node.get_outs() {
             print!("{},", output);
         }
-        println!(")");
+        match node.get_description() {
+            Some(description) => println!(") {}", description),
+            None => println!(")"),
+        }
     }
 }
 
+/// Renders the graph in DOT format, as `dot::render` does, grouping every task with the
+/// assets it produces in a cluster.
+pub fn render_clustered<W: std::io::Write>(graph: &Graph, out: &mut W) -> std::io::Result<()> {
+    let mut body = Vec::new();
+    dot::render(graph, &mut body)?;
+
+    // the dot crate has no support for subgraphs, they are appended before the closing brace
+    let body = String::from_utf8_lossy(&body);
+    let body = body.trim_end();
+    out.write_all(body.strip_suffix('}').unwrap_or(body).as_bytes())?;
+
+    // assets of each producer, side outputs included
+    let mut outputs: Map<&str, Vec<&str>> = Map::new();
+    for (asset, node) in graph.whatprovides.iter() {
+        outputs.entry(node.as_str()).or_default().push(asset.as_str());
+    }
+
+    for (name, _) in graph.iter() {
+        let outs = match outputs.get(name.as_str()) {
+            Some(outs) => outs,
+            None => continue,
+        };
+        writeln!(out, "    subgraph cluster_{} {{", name)?;
+        writeln!(out, "        label=\"{}\";", name)?;
+        writeln!(out, "        {};", name)?;
+        for output in outs {
+            writeln!(out, "        \"{}\"[shape=box];", output)?;
+            writeln!(out, "        {} -> \"{}\";", name, output)?;
+        }
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")
+}
+
 mod topo {
 
     use super::*;
 
     use std::collections::BTreeMap as Map;
-    use std::vec::Vec;
 
     type Nd<'a> = &'a str;
     type Ed<'a> = (Nd<'a>, Nd<'a>, &'a str, &'a str);
@@ -32,7 +68,19 @@ mod topo {
             dot::Id::new("rgraph").unwrap()
         }
         fn node_id(&'a self, n: &Nd) -> dot::Id<'a> {
-            dot::Id::new(format!("{}", n)).unwrap()
+            dot::Id::new(n.to_string()).unwrap()
+        }
+        fn node_label<'b>(&'b self, n: &Nd) -> dot::LabelText<'b> {
+            let mut label = n.to_string();
+            if let Some(description) = self.get_node(n).and_then(|node| node.get_description()) {
+                label.push_str(&format!("\n{}", description));
+            }
+            if let Some(entries) = self.metadata.get(*n) {
+                for (key, value) in entries {
+                    label.push_str(&format!("\n{}: {}", key, value));
+                }
+            }
+            dot::LabelText::LabelStr(label.into())
         }
         fn edge_label<'b>(&'b self, edge: &Ed) -> dot::LabelText<'b> {
             let &(_, _, from, to) = edge;
@@ -47,25 +95,26 @@ mod topo {
 
         fn edges(&'a self) -> dot::Edges<'a, Ed<'a>> {
             let mut ins: Map<&'a str, &'a str> = Map::new();
-            let mut out: Map<&'a str, &'a str> = Map::new();
-
-            let mut nodes: Vec<&'a str> = Vec::new();
 
             for (name, node) in self.iter() {
-                let name_str = name.as_str();
-                nodes.push(name_str);
                 for input in node.get_ins() {
-                    ins.insert(input.as_str(), name_str);
-                }
-                for output in node.get_outs() {
-                    out.insert(output.as_str(), name_str);
+                    ins.insert(input.as_str(), name.as_str());
                 }
             }
+            // side outputs are only known by the graph, not by their node
+            let out: Map<&'a str, &'a str> = self
+                .whatprovides
+                .iter()
+                .map(|(asset, node)| (asset.as_str(), node.as_str()))
+                .collect();
             self.bindings
                 .iter()
                 .map(|b| {
                     (
-                        *out.get(b.1.as_str()).expect("malformed graph"),
+                        // the source may be an output or an already bound input
+                        *out.get(b.1.as_str())
+                            .or_else(|| ins.get(b.1.as_str()))
+                            .expect("malformed graph"),
                         *ins.get(b.0.as_str()).expect("malformed graph"),
                         b.0.as_str(),
                         b.1.as_str(),
@@ -145,4 +194,74 @@ mod tests {
         let dot_text = String::from_utf8(output).unwrap();
         println!("{}", dot_text);
     }
+
+    #[test]
+    fn dot_metadata() {
+        let mut g = get_test_graph();
+        g.set_metadata("sink_1", "group", "sinks".into())
+            .expect("node exists");
+
+        let mut output = Vec::new();
+        dot::render(&g, &mut output).expect("it should draw");
+        let dot_text = String::from_utf8(output).unwrap();
+        println!("{}", dot_text);
+        assert!(dot_text.contains("sink_1[label=\"sink_1\\ngroup: sinks\"]"));
+
+        g.add_node(create_node!(
+                /// described task
+                described () -> () { }
+            )).unwrap();
+        let mut output = Vec::new();
+        dot::render(&g, &mut output).expect("it should draw");
+        let dot_text = String::from_utf8(output).unwrap();
+        assert!(dot_text.contains("described[label=\"described\\ndescribed task\"]"));
+    }
+
+    #[test]
+    fn dot_side_output() {
+        let mut g = get_test_graph();
+        g.declare_side_output("no_input", "no_input::extra")
+            .expect("node exists");
+        g.bind_asset("no_input::extra", "sink_2::name")
+            .expect("binding should exist");
+
+        let mut output = Vec::new();
+        dot::render(&g, &mut output).expect("it should draw");
+        let dot_text = String::from_utf8(output).unwrap();
+        println!("{}", dot_text);
+        assert!(dot_text.contains("no_input -> sink_2"));
+    }
+
+    #[test]
+    fn clusters() {
+        let mut g = get_test_graph();
+        g.bind_asset("no_input::i", "sink_1::input")
+            .expect("binding should exist");
+
+        let mut output = Vec::new();
+        render_clustered(&g, &mut output).expect("it should draw");
+        let dot_text = String::from_utf8(output).unwrap();
+        println!("{}", dot_text);
+
+        assert!(dot_text.contains("subgraph cluster_no_input {"));
+        assert!(dot_text.contains("\"no_input::i\"[shape=box];"));
+        assert!(!dot_text.contains("subgraph cluster_sink_1"));
+        assert!(dot_text.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn clusters_side_outputs() {
+        let mut g = get_test_graph();
+        g.declare_side_output("no_input", "no_input::extra")
+            .expect("node exists");
+
+        let mut output = Vec::new();
+        render_clustered(&g, &mut output).expect("it should draw");
+        let dot_text = String::from_utf8(output).unwrap();
+        println!("{}", dot_text);
+
+        assert!(dot_text.contains("\"no_input::extra\"[shape=box];"));
+        assert!(dot_text.contains("no_input -> \"no_input::extra\";"));
+        assert!(dot_text.matches('{').count() == dot_text.matches('}').count());
+    }
 }